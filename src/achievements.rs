@@ -0,0 +1,201 @@
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    events::{GameEvent, Observer},
+    storage
+};
+
+const FILENAME: &str = "achievements.json";
+
+// Boards of at most this many cells count as tiny: only the smallest, 8x8, does
+const TINY_BOARD_CELLS: usize = 64;
+const TIGHT_MAZE_FREEDOM: f64 = 0.5;
+const TIGHT_MAZE_APPLES: u32 = 50;
+// Ten minutes of game time
+const MARATHON_TIME: u64 = 10 * 60 * 1000;
+
+// How many tacts an unlocked achievement stays announced in the HUD
+const TOAST_TICKS: u64 = 30;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Achievement {
+    FirstBite,
+    TinyBoard,
+    TightMaze,
+    Marathon,
+    NoLeftTurns
+}
+
+pub const ACHIEVEMENTS: [Achievement; 5] = [
+    Achievement::FirstBite,
+    Achievement::TinyBoard,
+    Achievement::TightMaze,
+    Achievement::Marathon,
+    Achievement::NoLeftTurns
+];
+
+impl Achievement {
+    pub fn get_name(&self) -> &'static str {
+        match *self {
+            Achievement::FirstBite => "First bite",
+            Achievement::TinyBoard => "Tight quarters",
+            Achievement::TightMaze => "Labyrinth gourmet",
+            Achievement::Marathon => "Marathon",
+            Achievement::NoLeftTurns => "Zoolander"
+        }
+    }
+
+    pub fn get_description(&self) -> &'static str {
+        match *self {
+            Achievement::FirstBite => "eat an apple",
+            Achievement::TinyBoard => "clear an 8x8 board",
+            Achievement::TightMaze => "eat 50 apples in one game in a maze with freedom below 0.5",
+            Achievement::Marathon => "survive 10 minutes",
+            Achievement::NoLeftTurns => "clear a board without turning left"
+        }
+    }
+}
+
+// Achievements unlocked so far, in the order they were
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct UnlockedAchievements {
+    achievements: Vec<Achievement>
+}
+
+impl UnlockedAchievements {
+    pub fn load() -> UnlockedAchievements {
+        storage::load(FILENAME)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        storage::save(FILENAME, self)
+    }
+
+    pub fn has(&self, achievement: Achievement) -> bool {
+        self.achievements.contains(&achievement)
+    }
+}
+
+// Follows the events of one game and unlocks achievements as their conditions are met
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct AchievementTracker {
+    board_cells: usize,
+    // Only set for generated mazes
+    maze_freedom: Option<f64>,
+    apples: u32,
+    has_turned_left: bool,
+    // Games that don't count, like practice or ghost ones, have nothing loaded and unlock nothing
+    #[serde(skip)]
+    unlocked: Option<UnlockedAchievements>,
+    #[serde(skip)]
+    has_new: bool,
+    // Unlocked during this session, the last one is announced until `toast_until`
+    #[serde(skip)]
+    unlocked_now: Vec<Achievement>,
+    #[serde(skip)]
+    tick_count: u64,
+    #[serde(skip)]
+    toast_until: u64
+}
+
+impl Observer for AchievementTracker {
+    fn on_event(&mut self, event: &GameEvent) {
+        let met = match *event {
+            GameEvent::AppleEaten { kind } if kind.is_edible() => {
+                self.apples += 1;
+                let is_tight_maze = self.maze_freedom.is_some_and(|freedom| freedom < TIGHT_MAZE_FREEDOM);
+
+                let mut met = vec![Achievement::FirstBite];
+                if is_tight_maze && self.apples >= TIGHT_MAZE_APPLES {
+                    met.push(Achievement::TightMaze);
+                }
+                met
+            }
+            GameEvent::DirectionChanged { from, to } => {
+                self.has_turned_left |= to == from.turned_left();
+                vec![]
+            }
+            GameEvent::Tick { tick_count, elapsed_time } => {
+                self.tick_count = tick_count;
+                if elapsed_time >= MARATHON_TIME { vec![Achievement::Marathon] } else { vec![] }
+            }
+            // Reaching a target length or outliving opponents doesn't count
+            GameEvent::BoardCleared => {
+                let mut met = vec![];
+                if self.board_cells <= TINY_BOARD_CELLS {
+                    met.push(Achievement::TinyBoard);
+                }
+                if !self.has_turned_left {
+                    met.push(Achievement::NoLeftTurns);
+                }
+                met
+            }
+            _ => vec![]
+        };
+
+        self.unlock(met);
+    }
+}
+
+impl AchievementTracker {
+    pub fn new(board_cells: usize, maze_freedom: Option<f64>) -> AchievementTracker {
+        AchievementTracker { board_cells, maze_freedom, ..AchievementTracker::default() }
+    }
+
+    pub fn enable(&mut self) {
+        self.unlocked = Some(UnlockedAchievements::load());
+    }
+
+    fn unlock(&mut self, met: Vec<Achievement>) {
+        let Some(unlocked) = &mut self.unlocked else {
+            return;
+        };
+        let new: Vec<Achievement> = met.into_iter()
+            .filter(|achievement| !unlocked.has(*achievement))
+            .collect();
+        if new.is_empty() {
+            return;
+        }
+
+        unlocked.achievements.extend(&new);
+        self.unlocked_now.extend(new);
+        self.has_new = true;
+        self.toast_until = self.tick_count + TOAST_TICKS;
+    }
+
+    pub fn get_unlocked_now(&self) -> &[Achievement] {
+        &self.unlocked_now
+    }
+
+    // The achievement to announce in the HUD right now, if any
+    pub fn get_toast(&self) -> Option<Achievement> {
+        self.unlocked_now.last()
+            .copied()
+            .filter(|_| self.tick_count < self.toast_until)
+    }
+
+    // Only writes the file when something was unlocked
+    pub fn save(&self) -> io::Result<()> {
+        match &self.unlocked {
+            Some(unlocked) if self.has_new => unlocked.save(),
+            _ => Ok(())
+        }
+    }
+}
+
+// `snake achievements` lists every achievement and whether it's unlocked
+pub fn run() -> io::Result<()> {
+    let unlocked = UnlockedAchievements::load();
+    let count = ACHIEVEMENTS.iter().filter(|achievement| unlocked.has(**achievement)).count();
+
+    println!("Achievements unlocked: {}/{}", count, ACHIEVEMENTS.len());
+    for achievement in ACHIEVEMENTS {
+        let mark = if unlocked.has(achievement) { 'x' } else { ' ' };
+        println!("[{}] {}: {}", mark, achievement.get_name(), achievement.get_description());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+use crate::{
+    controller::{Controller, GameView},
+    direction::{Direction, DIRECTIONS},
+    point::Point
+};
+
+// Heads for the nearest apple, only avoiding cells that would kill it immediately
+pub struct GreedyController;
+
+impl Controller for GreedyController {
+    fn next_direction(&mut self, view: &GameView, snake_index: usize) -> Option<Direction> {
+        greedy_direction(view, snake_index)
+    }
+}
+
+// Follows an A* shortest path to the nearest apple
+pub struct ShortestPathController;
+
+impl Controller for ShortestPathController {
+    fn next_direction(&mut self, view: &GameView, snake_index: usize) -> Option<Direction> {
+        let snake = &view.get_snakes()[snake_index];
+        let head = *snake.get_head();
+
+        match find_path(view, head, snake.get_direction().opposite(), view.get_apples()) {
+            Some(path) => direction_towards(view, &head, &path[0]),
+            None => greedy_direction(view, snake_index)
+        }
+    }
+}
+
+// Follows the shortest path too, but only if there's enough room left
+// afterwards for the whole body; otherwise goes where there's the most space
+pub struct SurvivalController;
+
+impl Controller for SurvivalController {
+    fn next_direction(&mut self, view: &GameView, snake_index: usize) -> Option<Direction> {
+        let snake = &view.get_snakes()[snake_index];
+        let head = *snake.get_head();
+        let needed_space = snake.get_len();
+
+        if let Some(path) = find_path(view, head, snake.get_direction().opposite(), view.get_apples())
+                && count_reachable_cells(view, path[0], needed_space) >= needed_space {
+            return direction_towards(view, &head, &path[0]);
+        }
+
+        safe_directions(view, snake_index).into_iter()
+            .max_by_key(|direction| {
+                let next = view.step(&head, *direction);
+                (
+                    count_reachable_cells(view, next, needed_space),
+                    Reverse(distance_to_nearest_apple(view, &next))
+                )
+            })
+    }
+}
+
+fn greedy_direction(view: &GameView, snake_index: usize) -> Option<Direction> {
+    let head = *view.get_snakes()[snake_index].get_head();
+
+    safe_directions(view, snake_index).into_iter()
+        .min_by_key(|direction| distance_to_nearest_apple(view, &view.step(&head, *direction)))
+}
+
+pub fn safe_directions(view: &GameView, snake_index: usize) -> Vec<Direction> {
+    let snake = &view.get_snakes()[snake_index];
+    let head = snake.get_head();
+
+    DIRECTIONS.into_iter()
+        .filter(|direction| *direction != snake.get_direction().opposite())
+        .filter(|direction| !view.is_blocked(&view.step(head, *direction)))
+        .collect()
+}
+
+fn distance_to_nearest_apple(view: &GameView, point: &Point) -> u32 {
+    view.get_apples().iter()
+        .map(|apple| point.distance_to(apple))
+        .min()
+        .unwrap_or(u32::MAX)
+}
+
+pub fn direction_towards(view: &GameView, from: &Point, to: &Point) -> Option<Direction> {
+    DIRECTIONS.into_iter().find(|direction| view.step(from, *direction) == *to)
+}
+
+// A* over free cells; returns the path to the closest target without the start cell.
+// A target under the start doesn't count: the head moves on before it gets there.
+// The path never leaves the start going `back`, as snakes can't turn around
+pub fn find_path(view: &GameView, start: Point, back: Direction, targets: &[Point]) -> Option<Vec<Point>> {
+    if targets.is_empty() {
+        return None;
+    }
+
+    let board = view.get_board();
+    let heuristic = |point: &Point| {
+        targets.iter().map(|target| point.distance_to(target)).min().unwrap_or(0)
+    };
+
+    let mut cost = vec![vec![u32::MAX; board.get_width()]; board.get_height()];
+    let mut parent: Vec<Vec<Option<Point>>> = vec![vec![None; board.get_width()]; board.get_height()];
+    let mut queue = BinaryHeap::new();
+
+    cost[start.get_y() as usize][start.get_x() as usize] = 0;
+    queue.push(Reverse((heuristic(&start), 0, start.get_x(), start.get_y())));
+
+    while let Some(Reverse((_, current_cost, x, y))) = queue.pop() {
+        let current = Point::new(x, y);
+
+        if current != start && targets.contains(&current) {
+            let mut path = vec![current];
+            let mut step = current;
+            while let Some(previous) = parent[step.get_y() as usize][step.get_x() as usize]
+                    && previous != start {
+                path.push(previous);
+                step = previous;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        if current_cost > cost[y as usize][x as usize] {
+            continue;
+        }
+
+        for direction in DIRECTIONS {
+            let next = view.step(&current, direction);
+            if view.is_blocked(&next) || (current == start && direction == back) {
+                continue;
+            }
+
+            let next_cost = current_cost + 1;
+            let (next_x, next_y) = (next.get_x() as usize, next.get_y() as usize);
+            if next_cost < cost[next_y][next_x] {
+                cost[next_y][next_x] = next_cost;
+                parent[next_y][next_x] = Some(current);
+                queue.push(Reverse((next_cost + heuristic(&next), next_cost, next.get_x(), next.get_y())));
+            }
+        }
+    }
+
+    None
+}
+
+// Flood fill from `start`, which itself counts as free; stops early once `limit` is reached
+pub fn count_reachable_cells(view: &GameView, start: Point, limit: usize) -> usize {
+    if !view.is_inside(&start) || view.is_wall(&start) {
+        return 0;
+    }
+
+    let board = view.get_board();
+    let mut visited = vec![vec![false; board.get_width()]; board.get_height()];
+    let mut queue = VecDeque::from([start]);
+    let mut count = 0;
+
+    visited[start.get_y() as usize][start.get_x() as usize] = true;
+
+    while let Some(current) = queue.pop_front() {
+        count += 1;
+        if count >= limit {
+            break;
+        }
+
+        for direction in DIRECTIONS {
+            let next = view.step(&current, direction);
+            if view.is_blocked(&next) || visited[next.get_y() as usize][next.get_x() as usize] {
+                continue;
+            }
+            visited[next.get_y() as usize][next.get_x() as usize] = true;
+            queue.push_back(next);
+        }
+    }
+
+    count
+}
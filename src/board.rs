@@ -1,8 +1,11 @@
+use serde::{Deserialize, Serialize};
+
 use crate::point::Point;
 
 const MIN_SIDE: u32 = 8;
 const MAX_SIDE: u32 = 1000;
 
+#[derive(Serialize, Deserialize)]
 pub struct Board {
     width: usize,
     height: usize,
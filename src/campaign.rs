@@ -0,0 +1,136 @@
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::Config,
+    game::Game,
+    storage
+};
+
+const PROGRESS_FILENAME: &str = "campaign.json";
+
+// Generated levels of the default campaign grow and get more mazy one after another
+const DEFAULT_LEVEL_COUNT: u32 = 6;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct CampaignConfig {
+    levels: Vec<CampaignLevel>
+}
+
+impl Default for CampaignConfig {
+    fn default() -> CampaignConfig {
+        let levels = (0..DEFAULT_LEVEL_COUNT)
+            .map(|index| CampaignLevel {
+                width: 24 + 6 * index,
+                height: 12 + 3 * index,
+                enable_maze: index > 0,
+                maze_freedom: 0.95 - 0.05 * index as f64,
+                level_file: None,
+                target_length: 8 + 4 * index as usize
+            })
+            .collect();
+
+        CampaignConfig { levels }
+    }
+}
+
+impl CampaignConfig {
+    pub fn get_levels(&self) -> &[CampaignLevel] {
+        &self.levels
+    }
+}
+
+// One step of the campaign: a generated board or a hand-authored level,
+// cleared by growing the snake to `target_length`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct CampaignLevel {
+    width: u32,
+    height: u32,
+    enable_maze: bool,
+    maze_freedom: f64,
+    level_file: Option<String>,
+    target_length: usize
+}
+
+impl Default for CampaignLevel {
+    fn default() -> CampaignLevel {
+        CampaignLevel {
+            width: 30,
+            height: 15,
+            enable_maze: true,
+            maze_freedom: 0.9,
+            level_file: None,
+            target_length: 10
+        }
+    }
+}
+
+impl CampaignLevel {
+    // Everything not set by the level, like speed or food, comes from the main config
+    fn apply(&self, config: Config) -> Config {
+        config.with_board(self.width, self.height)
+            .with_maze(self.enable_maze, self.maze_freedom)
+            .with_level_file(self.level_file.clone())
+            .with_target_length(Some(self.target_length))
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct CampaignProgress {
+    // Levels are cleared in order, so the next one is always the only new unlock
+    cleared_levels: usize
+}
+
+// `level` is the 1-based number of an unlocked level to start from; otherwise
+// the campaign goes on from the first level not cleared yet
+pub fn run(config: Config, level: Option<usize>) -> io::Result<()> {
+    let levels = config.get_campaign().get_levels().to_vec();
+    if levels.is_empty() {
+        println!("The campaign has no levels. Check the [campaign] section of the config");
+        return Ok(());
+    }
+
+    let mut progress: CampaignProgress = storage::load(PROGRESS_FILENAME);
+    let last_unlocked = progress.cleared_levels.min(levels.len() - 1);
+
+    let mut index = match level {
+        Some(number) if number == 0 || number > levels.len() => {
+            println!("There's no level {number}, the campaign has {} levels", levels.len());
+            return Ok(());
+        }
+        Some(number) if number - 1 > last_unlocked => {
+            println!("Level {number} is locked. Levels up to {} are unlocked", last_unlocked + 1);
+            return Ok(());
+        }
+        Some(number) => number - 1,
+        None => last_unlocked
+    };
+
+    loop {
+        let mut game = Game::new(levels[index].apply(config.clone()));
+        game.set_title(format!("Level {}/{}", index + 1, levels.len()));
+        game.disable_saving();
+        game.start()?;
+
+        if !game.has_user_won() {
+            break;
+        }
+
+        if index == progress.cleared_levels {
+            progress.cleared_levels += 1;
+            storage::save(PROGRESS_FILENAME, &progress)?;
+        }
+
+        index += 1;
+        if index == levels.len() {
+            println!("The campaign is complete!");
+            break;
+        }
+        println!("Level {} unlocked", index + 1);
+    }
+
+    Ok(())
+}
@@ -1,24 +1,147 @@
 use std::fs;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::Board,
+    level::Level,
+    theme::Theme,
+    controller::ControllerKind,
+    difficulty::DifficultyCurve,
+    food::FoodConfig,
+    power_up::PowerUpConfig,
+    maze_shifter::ShiftingMazeConfig,
+    vision::VisionConfig,
+    campaign::CampaignConfig,
+    rewind::RewindConfig,
+    game_mode::GameMode,
+    render_mode::RenderMode,
+    palette::Palette,
+    cues::CuesConfig
+};
 
 const FILENAME: &str = "config.toml";
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Config {
     width: u32,
     height: u32,
     pause_time: u32,
     enable_maze: bool,
-    maze_freedom: f64
+    maze_freedom: f64,
+    #[serde(default)]
+    portal_pairs: u32,
+    #[serde(default)]
+    level_file: Option<String>,
+    #[serde(default)]
+    target_length: Option<usize>,
+    #[serde(default)]
+    render_mode: RenderMode,
+    #[serde(default)]
+    theme: Option<String>,
+    #[serde(default)]
+    palette: Palette,
+    #[serde(default)]
+    vertical_debt: bool,
+    #[serde(default)]
+    mode: GameMode,
+    #[serde(default)]
+    controller: ControllerKind,
+    #[serde(default)]
+    opponents: Vec<OpponentConfig>,
+    #[serde(default)]
+    difficulty: DifficultyCurve,
+    #[serde(default)]
+    food: FoodConfig,
+    #[serde(default)]
+    power_ups: PowerUpConfig,
+    #[serde(default)]
+    shifting_maze: ShiftingMazeConfig,
+    #[serde(default)]
+    vision: VisionConfig,
+    #[serde(default)]
+    campaign: CampaignConfig,
+    #[serde(default)]
+    rewind: RewindConfig,
+    // Practice games can be rewound, but aren't ranked
+    #[serde(default)]
+    practice: bool,
+    #[serde(default)]
+    cues: CuesConfig
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OpponentConfig {
+    controller: ControllerKind
 }
 
 impl Config {
+    // Only the required entries, everything else is left at its default
+    pub fn new(width: u32, height: u32, pause_time: u32, enable_maze: bool, maze_freedom: f64) -> Config {
+        Config {
+            width,
+            height,
+            pause_time,
+            enable_maze,
+            maze_freedom,
+            portal_pairs: 0,
+            level_file: None,
+            target_length: None,
+            render_mode: RenderMode::default(),
+            theme: None,
+            palette: Palette::default(),
+            vertical_debt: false,
+            mode: GameMode::default(),
+            controller: ControllerKind::default(),
+            opponents: vec![],
+            difficulty: DifficultyCurve::default(),
+            food: FoodConfig::default(),
+            power_ups: PowerUpConfig::default(),
+            shifting_maze: ShiftingMazeConfig::default(),
+            vision: VisionConfig::default(),
+            campaign: CampaignConfig::default(),
+            rewind: RewindConfig::default(),
+            practice: false,
+            cues: CuesConfig::default()
+        }
+    }
+    
     pub fn read() -> Config {
+        Config::try_read().unwrap_or_else(|error| panic!("{error}"))
+    }
+    
+    pub fn try_read() -> Result<Config, String> {
         let config_data = fs::read_to_string(FILENAME)
-            .expect(&format!("Failed to read {FILENAME}. Check it again"));
+            .map_err(|_| format!("Failed to read {FILENAME}. Check it again"))?;
+        
+        let config: Config = toml::from_str(&config_data)
+            .map_err(|_| format!("Failed to parse {FILENAME}. Check it again"))?;
+        config.validate()?;
+        
+        Ok(config)
+    }
+    
+    // Catches what would otherwise stop the game as soon as it's built
+    pub fn validate(&self) -> Result<(), String> {
+        let (width, height) = match &self.level_file {
+            Some(level_file) => {
+                let level = Level::try_read(level_file)?;
+                (level.get_width(), level.get_height())
+            }
+            None => (self.width, self.height)
+        };
+        if Board::new(width, height).is_none() {
+            return Err(format!("Invalid Board configuration: ({width}, {height})"));
+        }
+        check_chance("maze_freedom", self.maze_freedom)?;
+        check_chance("power_ups.chance", self.power_ups.get_chance())?;
+        if let GameMode::Survival { food_chance, .. } = self.mode {
+            check_chance("mode.food_chance", food_chance)?;
+        }
+        if let Some(theme) = &self.theme {
+            Theme::try_load(theme)?;
+        }
         
-        toml::from_str(&config_data)
-            .expect(&format!("Failed to parse {FILENAME}. Check it again"))
+        Ok(())
     }
     
     pub fn get_width(&self) -> u32 {
@@ -40,4 +163,142 @@ impl Config {
     pub fn get_maze_freedom(&self) -> f64 {
         self.maze_freedom
     }
+    
+    pub fn get_portal_pairs(&self) -> u32 {
+        self.portal_pairs
+    }
+    
+    pub fn get_level_file(&self) -> Option<&str> {
+        self.level_file.as_deref()
+    }
+    
+    pub fn get_target_length(&self) -> Option<usize> {
+        self.target_length
+    }
+    
+    pub fn with_board(self, width: u32, height: u32) -> Config {
+        Config { width, height, ..self }
+    }
+    
+    pub fn with_maze(self, enable_maze: bool, maze_freedom: f64) -> Config {
+        Config { enable_maze, maze_freedom, ..self }
+    }
+    
+    // No maze, portals or opponents, and an even number of rows, so there's a cycle over the whole board
+    pub fn with_open_board(self) -> Config {
+        Config {
+            height: self.height - self.height % 2,
+            enable_maze: false,
+            portal_pairs: 0,
+            level_file: None,
+            opponents: vec![],
+            ..self
+        }
+    }
+    
+    pub fn with_level_file(self, level_file: Option<String>) -> Config {
+        Config { level_file, ..self }
+    }
+    
+    pub fn with_target_length(self, target_length: Option<usize>) -> Config {
+        Config { target_length, ..self }
+    }
+    
+    pub fn get_render_mode(&self) -> RenderMode {
+        self.render_mode
+    }
+    
+    pub fn get_theme(&self) -> Option<&str> {
+        self.theme.as_deref()
+    }
+    
+    pub fn get_palette(&self) -> Palette {
+        self.palette
+    }
+    
+    pub fn has_vertical_debt(&self) -> bool {
+        self.vertical_debt
+    }
+    
+    pub fn get_mode(&self) -> GameMode {
+        self.mode
+    }
+    
+    pub fn get_controller(&self) -> ControllerKind {
+        self.controller
+    }
+    
+    pub fn with_controller(self, controller: ControllerKind) -> Config {
+        Config { controller, ..self }
+    }
+    
+    pub fn get_opponents(&self) -> &[OpponentConfig] {
+        &self.opponents
+    }
+    
+    pub fn get_difficulty(&self) -> DifficultyCurve {
+        self.difficulty
+    }
+    
+    pub fn get_food(&self) -> &FoodConfig {
+        &self.food
+    }
+    
+    pub fn get_power_ups(&self) -> &PowerUpConfig {
+        &self.power_ups
+    }
+    
+    pub fn get_shifting_maze(&self) -> &ShiftingMazeConfig {
+        &self.shifting_maze
+    }
+    
+    pub fn get_vision(&self) -> &VisionConfig {
+        &self.vision
+    }
+    
+    pub fn get_campaign(&self) -> &CampaignConfig {
+        &self.campaign
+    }
+    
+    pub fn get_rewind(&self) -> &RewindConfig {
+        &self.rewind
+    }
+    
+    pub fn get_cues(&self) -> &CuesConfig {
+        &self.cues
+    }
+    
+    // How the game looks and sounds, taken from another config without touching how it plays
+    pub fn with_presentation(self, other: &Config) -> Config {
+        Config {
+            render_mode: other.render_mode,
+            theme: other.theme.clone(),
+            palette: other.palette,
+            cues: other.cues.clone(),
+            ..self
+        }
+    }
+    
+    pub fn is_practice(&self) -> bool {
+        self.practice
+    }
+    
+    pub fn with_practice(self) -> Config {
+        Config { practice: true, ..self }
+    }
+}
+
+// Chances are drawn with `random_bool`, which only takes 0 to 1
+fn check_chance(name: &str, chance: f64) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&chance) {
+        return Err(format!("{name} has to be between 0 and 1, not {chance}"));
+    }
+    
+    Ok(())
+}
+
+impl OpponentConfig {
+    pub fn get_controller(&self) -> ControllerKind {
+        self.controller
+    }
 }
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ai::{GreedyController, ShortestPathController, SurvivalController},
+    board::Board,
+    direction::Direction,
+    hamiltonian::HamiltonianController,
+    maze::Portal,
+    point::Point,
+    snake::Snake
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ControllerKind {
+    #[default]
+    Keyboard,
+    Greedy,
+    ShortestPath,
+    Survival,
+    Hamiltonian
+}
+
+pub trait Controller {
+    // Returns the direction the snake at `snake_index` should turn to,
+    // or None to keep going the same way
+    fn next_direction(&mut self, view: &GameView, snake_index: usize) -> Option<Direction>;
+}
+
+pub fn build_controller(kind: ControllerKind) -> Box<dyn Controller> {
+    match kind {
+        ControllerKind::Keyboard => Box::new(KeyboardController),
+        ControllerKind::Greedy => Box::new(GreedyController),
+        ControllerKind::ShortestPath => Box::new(ShortestPathController),
+        ControllerKind::Survival => Box::new(SurvivalController),
+        ControllerKind::Hamiltonian => Box::new(HamiltonianController::new())
+    }
+}
+
+pub struct KeyboardController;
+
+impl Controller for KeyboardController {
+    fn next_direction(&mut self, view: &GameView, _snake_index: usize) -> Option<Direction> {
+        view.get_pressed_direction()
+    }
+}
+
+// Read-only snapshot of everything a controller may look at during a tick
+pub struct GameView<'a> {
+    board: &'a Board,
+    maze: Option<&'a [Vec<bool>]>,
+    // Changes whenever the maze does, so controllers know when to rethink it
+    maze_version: u64,
+    portals: &'a [Portal],
+    apples: Vec<Point>,
+    snakes: &'a [Snake],
+    pressed_direction: Option<Direction>
+}
+
+impl<'a> GameView<'a> {
+    pub fn new(board: &'a Board, maze: Option<&'a [Vec<bool>]>, maze_version: u64, portals: &'a [Portal],
+            apples: Vec<Point>, snakes: &'a [Snake], pressed_direction: Option<Direction>) -> GameView<'a> {
+        GameView { board, maze, maze_version, portals, apples, snakes, pressed_direction }
+    }
+
+    pub fn get_board(&self) -> &Board {
+        self.board
+    }
+
+    pub fn get_maze(&self) -> Option<&[Vec<bool>]> {
+        self.maze
+    }
+
+    pub fn get_maze_version(&self) -> u64 {
+        self.maze_version
+    }
+
+    pub fn get_portals(&self) -> &[Portal] {
+        self.portals
+    }
+
+    pub fn get_apples(&self) -> &[Point] {
+        &self.apples
+    }
+
+    pub fn get_snakes(&self) -> &[Snake] {
+        self.snakes
+    }
+
+    pub fn get_pressed_direction(&self) -> Option<Direction> {
+        self.pressed_direction
+    }
+
+    pub fn is_inside(&self, point: &Point) -> bool {
+        point.get_x() >= 0 && point.get_y() >= 0 &&
+            point.get_x() < self.board.get_width() as i32 &&
+            point.get_y() < self.board.get_height() as i32
+    }
+
+    pub fn is_wall(&self, point: &Point) -> bool {
+        match self.maze {
+            Some(maze) => maze[point.get_y() as usize][point.get_x() as usize],
+            None => false
+        }
+    }
+
+    // Where a snake ends up after moving from `point`, taking portals into account
+    pub fn step(&self, point: &Point, direction: Direction) -> Point {
+        let next = point.moved(direction);
+
+        for (entrance, exit) in self.portals {
+            if next == *entrance {
+                return *exit;
+            }
+            if next == *exit {
+                return *entrance;
+            }
+        }
+
+        next
+    }
+
+    // A cell is blocked if stepping on it would kill a snake right away
+    pub fn is_blocked(&self, point: &Point) -> bool {
+        !self.is_inside(point) ||
+            self.is_wall(point) ||
+            self.snakes.iter().any(|snake| snake.get_segments().contains(point))
+    }
+}
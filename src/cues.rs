@@ -0,0 +1,121 @@
+use std::io::{Write, stdout};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::events::{GameEvent, Observer};
+
+// Reverse video, the terminal's own visual bell
+const FLASH_ON: &str = "\x1b[?5h";
+const FLASH_OFF: &str = "\x1b[?5l";
+const FLASH_TIME: Duration = Duration::from_millis(60);
+const BELL: &str = "\x07";
+
+// Death repeats the signal, so it can't be mistaken for an apple
+const DEATH_REPEATS: usize = 3;
+const DEATH_GAP: Duration = Duration::from_millis(150);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Signal {
+    #[default]
+    Off,
+    Bell,
+    Flash
+}
+
+// What happens on one kind of event: a signal in the terminal, a command of one's own, or both
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Cue {
+    signal: Signal,
+    command: Option<String>
+}
+
+impl Cue {
+    fn is_enabled(&self) -> bool {
+        self.signal != Signal::Off || self.command.is_some()
+    }
+}
+
+// Everything is off unless turned on per event
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct CuesConfig {
+    apple_eaten: Cue,
+    // The head is right in front of a wall or the border
+    near_miss: Cue,
+    death: Cue
+}
+
+impl CuesConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.apple_eaten.is_enabled() || self.near_miss.is_enabled() || self.death.is_enabled()
+    }
+}
+
+// Plays the cues as the events come
+pub struct CuePlayer {
+    config: CuesConfig,
+    // Commands still running, so they're reaped once they finish
+    children: Vec<Child>
+}
+
+impl CuePlayer {
+    pub fn new(config: &CuesConfig) -> CuePlayer {
+        CuePlayer { config: config.clone(), children: vec![] }
+    }
+
+    fn play(&mut self, cue: Cue, name: &str, repeats: usize) {
+        for repeat in 0..repeats {
+            if repeat > 0 {
+                thread::sleep(DEATH_GAP);
+            }
+            match cue.signal {
+                Signal::Off => {}
+                Signal::Bell => print!("{BELL}"),
+                Signal::Flash => {
+                    print!("{FLASH_ON}");
+                    let _ = stdout().flush();
+                    thread::sleep(FLASH_TIME);
+                    print!("{FLASH_OFF}");
+                }
+            }
+            let _ = stdout().flush();
+        }
+
+        if let Some(command) = &cue.command {
+            self.run(command, name);
+        }
+    }
+
+    // The command runs in the background with the event's name in SNAKE_EVENT; a failing one is ignored
+    fn run(&mut self, command: &str, name: &str) {
+        self.children.retain_mut(|child| matches!(child.try_wait(), Ok(None)));
+
+        let (shell, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+        let child = Command::new(shell)
+            .args([flag, command])
+            .env("SNAKE_EVENT", name)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        if let Ok(child) = child {
+            self.children.push(child);
+        }
+    }
+}
+
+impl Observer for CuePlayer {
+    fn on_event(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::AppleEaten { kind } if kind.is_edible() => self.play(self.config.apple_eaten.clone(), "apple_eaten", 1),
+            GameEvent::NearMiss => self.play(self.config.near_miss.clone(), "near_miss", 1),
+            GameEvent::SnakeDied { .. } => self.play(self.config.death.clone(), "death", DEATH_REPEATS),
+            _ => {}
+        }
+    }
+}
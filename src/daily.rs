@@ -0,0 +1,136 @@
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    config::Config,
+    game::Game,
+    ghost::{BestRuns, Ghost},
+    scores::{ScoreEntry, ScoreStore}
+};
+
+const MODE: &str = "daily";
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+// Everyone playing on the same UTC day gets the same board, speed, maze and foods;
+// only the first game of the day is scored, the rest are practice against the best run so far.
+// The player's own config, if there's one, only decides how the game looks and sounds
+pub fn run(user_config: Option<Config>) -> io::Result<()> {
+    let days = SystemTime::now().duration_since(UNIX_EPOCH)
+        .expect("The clock is set before 1970")
+        .as_secs() / SECONDS_PER_DAY;
+    let date = format_date(days);
+
+    let mut scores = ScoreStore::load();
+    let previous_score = scores.find(MODE, &date).map(|entry| entry.get_score());
+
+    let (config, seed) = build_challenge(days);
+    let config = match &user_config {
+        Some(user_config) => config.with_presentation(user_config),
+        None => config
+    };
+    let mut best_runs = BestRuns::load();
+    let mut game = Game::with_seed(config.clone(), seed);
+    if let Some(best_run) = best_runs.find(seed) {
+        game.set_ghost(Ghost::new(Game::with_seed(config, seed), best_run.get_recording().clone()));
+    }
+    game.set_title(match previous_score {
+        Some(score) => format!("Daily challenge {date}, practice (today's score: {score})"),
+        None => format!("Daily challenge {date}")
+    });
+    game.disable_saving();
+    game.start()?;
+
+    // A saved game isn't finished, so it's neither scored nor raced against
+    if game.has_user_saved() {
+        return Ok(());
+    }
+
+    if best_runs.record(seed, game.calculate_user_score(), game.get_recording().clone()) {
+        best_runs.save()?;
+    }
+
+    if previous_score.is_none() {
+        let score = game.calculate_user_score();
+        scores.record(ScoreEntry::new(date, MODE, score));
+        scores.save()?;
+        println!("Today's score: {score}");
+    }
+
+    Ok(())
+}
+
+// The config and game seed for the given day since the epoch
+fn build_challenge(days: u64) -> (Config, u64) {
+    let mut rng = ChaCha8Rng::seed_from_u64(days);
+
+    let width = rng.random_range(30..=50);
+    let height = rng.random_range(15..=25);
+    let pause_time = rng.random_range(60..=100);
+    let maze_freedom = rng.random_range(0.7..0.9);
+
+    (Config::new(width, height, pause_time, true, maze_freedom), rng.random())
+}
+
+// Days since the epoch as a YYYY-MM-DD date, see http://howardhinnant.github.io/date_algorithms.html
+fn format_date(days: u64) -> String {
+    let days = days as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn describe_challenge(days: u64) -> String {
+        let (config, seed) = build_challenge(days);
+        format!("{}x{} {} {:.3} {seed}", config.get_width(), config.get_height(), config.get_pause_time(),
+            config.get_maze_freedom())
+    }
+
+    #[test]
+    fn same_day_gives_same_challenge() {
+        for days in [0, 11_016, 20_745] {
+            let (config, seed) = build_challenge(days);
+            let (other_config, other_seed) = build_challenge(days);
+
+            assert_eq!(seed, other_seed);
+            assert_eq!(serde_json::to_string(&config).unwrap(), serde_json::to_string(&other_config).unwrap());
+        }
+        assert_ne!(describe_challenge(20_745), describe_challenge(20_746));
+    }
+
+    // Players on any machine and any build have to get the same game
+    #[test]
+    fn challenge_is_stable_across_builds() {
+        assert_eq!(describe_challenge(20_745), "50x15 94 0.713 13957263118860617783");
+    }
+
+    #[test]
+    fn dates_are_formatted_in_utc() {
+        let dates = [
+            (0, "1970-01-01"),
+            (59, "1970-03-01"),
+            (11_016, "2000-02-29"),
+            (11_017, "2000-03-01"),
+            (20_088, "2024-12-31"),
+            (20_745, "2026-10-19")
+        ];
+
+        for (days, date) in dates {
+            assert_eq!(format_date(days), date);
+        }
+    }
+}
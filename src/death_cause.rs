@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum DeathCause {
+    Border,
+    MazeWall,
+    // `segment` counts from the head, which is segment 0
+    OwnBody {
+        segment: usize
+    },
+    OtherSnake,
+    // Went hungry for too long in survival mode
+    Starvation
+}
+
+impl DeathCause {
+    pub fn describe(&self) -> String {
+        match *self {
+            DeathCause::Border => String::from("hit the border"),
+            DeathCause::MazeWall => String::from("hit a maze wall"),
+            DeathCause::OwnBody { segment } => format!("bit own body at segment {segment}"),
+            DeathCause::OtherSnake => String::from("hit another snake"),
+            DeathCause::Starvation => String::from("starved")
+        }
+    }
+
+    // The kind of death, without the details
+    pub fn get_name(&self) -> &'static str {
+        match *self {
+            DeathCause::Border => "border",
+            DeathCause::MazeWall => "maze wall",
+            DeathCause::OwnBody { .. } => "own body",
+            DeathCause::OtherSnake => "other snake",
+            DeathCause::Starvation => "starvation"
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+// How the pause between tacts shrinks as the score grows
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+#[serde(tag = "curve", rename_all = "snake_case")]
+pub enum DifficultyCurve {
+    #[default]
+    Constant,
+    // `step` milliseconds less for every apple
+    Linear {
+        step: u32,
+        min_pause_time: u32
+    },
+    // `step` milliseconds less for every `apples_per_step` apples
+    Stepped {
+        step: u32,
+        apples_per_step: u32,
+        min_pause_time: u32
+    },
+    // Pause time gets multiplied by `factor` for every apple
+    Exponential {
+        factor: f64,
+        min_pause_time: u32
+    }
+}
+
+impl DifficultyCurve {
+    pub fn get_pause_time(&self, base_pause_time: u32, score: u32) -> u32 {
+        match *self {
+            DifficultyCurve::Constant => base_pause_time,
+            DifficultyCurve::Linear { step, min_pause_time } => {
+                base_pause_time.saturating_sub(step.saturating_mul(score)).max(min_pause_time)
+            }
+            DifficultyCurve::Stepped { step, apples_per_step, min_pause_time } => {
+                let steps = score / apples_per_step.max(1);
+                base_pause_time.saturating_sub(step.saturating_mul(steps)).max(min_pause_time)
+            }
+            DifficultyCurve::Exponential { factor, min_pause_time } => {
+                let pause_time = base_pause_time as f64 * factor.powi(score as i32);
+                (pause_time.round() as u32).max(min_pause_time)
+            }
+        }
+    }
+}
@@ -1,6 +1,9 @@
 // use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-#[derive(PartialEq, Copy, Clone)]
+#[allow(clippy::upper_case_acronyms)]
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Direction {
     UP,
     DOWN,
@@ -8,15 +11,34 @@ pub enum Direction {
     RIGHT
 }
 
+pub const DIRECTIONS: [Direction; 4] = [
+    Direction::UP,
+    Direction::DOWN,
+    Direction::LEFT,
+    Direction::RIGHT
+];
+
 impl Direction {
     pub fn is_vertical(&self) -> bool {
+        matches!(*self, Direction::UP | Direction::DOWN)
+    }
+    
+    pub fn opposite(&self) -> Direction {
+        match *self {
+            Direction::UP => Direction::DOWN,
+            Direction::DOWN => Direction::UP,
+            Direction::LEFT => Direction::RIGHT,
+            Direction::RIGHT => Direction::LEFT
+        }
+    }
+    
+    // Where the snake heads after turning left, as seen from the snake
+    pub fn turned_left(&self) -> Direction {
         match *self {
-            Direction::UP | Direction::DOWN => {
-                true
-            }
-            _ => {
-                false
-            }
+            Direction::UP => Direction::LEFT,
+            Direction::LEFT => Direction::DOWN,
+            Direction::DOWN => Direction::RIGHT,
+            Direction::RIGHT => Direction::UP
         }
     }
     
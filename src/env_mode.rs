@@ -0,0 +1,200 @@
+use std::cell::RefCell;
+use std::io::{self, BufRead, Write};
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::Config,
+    controller::ControllerKind,
+    death_cause::DeathCause,
+    direction::Direction,
+    events::{GameEvent, Observer},
+    food::FoodKind,
+    game::Game
+};
+
+const SCORE_REWARD: f64 = 1.0;
+const DEATH_REWARD: f64 = -1.0;
+
+// One JSON object per line on stdin, e.g.
+// {"command": "reset", "seed": 42} or {"command": "step", "action": "up"}
+#[derive(Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+enum Request {
+    Reset {
+        seed: Option<u64>,
+        config: Option<Box<Config>>
+    },
+    Step {
+        action: Option<Direction>
+    }
+}
+
+#[derive(Serialize)]
+struct Observation {
+    grid: Vec<String>,
+    head: [i32; 2],
+    direction: Direction,
+    // The nearest edible food, kept for agents written before there were other kinds
+    apple: Option<[i32; 2]>,
+    foods: Vec<FoodObservation>,
+    effects: Vec<EffectObservation>,
+    score: u32
+}
+
+#[derive(Serialize)]
+struct EffectObservation {
+    name: &'static str,
+    remaining_ticks: u64
+}
+
+#[derive(Serialize)]
+struct FoodObservation {
+    kind: FoodKind,
+    position: [i32; 2]
+}
+
+#[derive(Serialize)]
+struct StepInfo {
+    death_cause: Option<DeathCause>,
+    won: bool,
+    // Everything that happened during the step
+    events: Vec<GameEvent>
+}
+
+// Collects the events of the game, to be handed to the agent after every step
+#[derive(Clone, Default)]
+struct EventQueue(Rc<RefCell<Vec<GameEvent>>>);
+
+impl Observer for EventQueue {
+    fn on_event(&mut self, event: &GameEvent) {
+        self.0.borrow_mut().push(*event);
+    }
+}
+
+impl EventQueue {
+    fn take(&self) -> Vec<GameEvent> {
+        self.0.take()
+    }
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Response {
+    Reset {
+        observation: Observation
+    },
+    Step {
+        observation: Observation,
+        reward: f64,
+        done: bool,
+        info: StepInfo
+    },
+    Error {
+        error: String
+    }
+}
+
+// Runs the game headless at full speed for training agents, answering every request with one JSON line
+pub fn run() -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    let mut game: Option<Game> = None;
+    let events = EventQueue::default();
+
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => handle_request(request, &mut game, &events),
+            Err(error) => Response::Error { error: error.to_string() }
+        };
+
+        serde_json::to_writer(&mut stdout, &response)?;
+        writeln!(stdout)?;
+        stdout.flush()?;
+    }
+
+    Ok(())
+}
+
+fn handle_request(request: Request, game: &mut Option<Game>, events: &EventQueue) -> Response {
+    match request {
+        Request::Reset { seed, config } => {
+            // The agent's actions always drive the player, whatever the config says
+            let config = match config {
+                Some(config) => config.validate().map(|_| *config),
+                None => Config::try_read()
+            };
+            let config = match config {
+                Ok(config) => config.with_controller(ControllerKind::Keyboard),
+                Err(error) => return Response::Error { error }
+            };
+            let mut new_game = Game::with_seed(config, seed.unwrap_or_else(rand::random));
+            new_game.subscribe(Box::new(events.clone()));
+            events.take();
+
+            let observation = observe(&new_game);
+            *game = Some(new_game);
+
+            Response::Reset { observation }
+        }
+        Request::Step { action } => {
+            let Some(game) = game else {
+                return Response::Error { error: String::from("No game in progress, send reset first") };
+            };
+            if game.is_over() {
+                return Response::Error { error: String::from("The game is over, send reset to start a new one") };
+            }
+
+            let score_before = game.calculate_user_score();
+            game.tick(action);
+
+            let reward = if game.get_death_cause().is_some() {
+                DEATH_REWARD
+            } else {
+                (game.calculate_user_score() as i64 - score_before as i64) as f64 * SCORE_REWARD
+            };
+
+            Response::Step {
+                observation: observe(game),
+                reward,
+                done: game.is_over(),
+                info: StepInfo {
+                    death_cause: game.get_death_cause(),
+                    won: game.has_user_won(),
+                    events: events.take()
+                }
+            }
+        }
+    }
+}
+
+fn observe(game: &Game) -> Observation {
+    let player = game.get_player();
+    let head = player.get_head();
+
+    Observation {
+        grid: game.build_field().into_iter().map(String::from_iter).collect(),
+        head: [head.get_x(), head.get_y()],
+        direction: player.get_direction(),
+        apple: game.get_foods().iter()
+            .filter(|food| food.get_kind().is_edible())
+            .map(|food| food.get_position())
+            .min_by_key(|position| head.distance_to(position))
+            .map(|position| [position.get_x(), position.get_y()]),
+        foods: game.get_foods().iter()
+            .map(|food| FoodObservation {
+                kind: food.get_kind(),
+                position: [food.get_position().get_x(), food.get_position().get_y()]
+            })
+            .collect(),
+        effects: game.get_effects().into_iter()
+            .map(|(effect, remaining_ticks)| EffectObservation { name: effect.get_name(), remaining_ticks })
+            .collect(),
+        score: game.calculate_user_score()
+    }
+}
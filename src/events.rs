@@ -0,0 +1,54 @@
+use serde::Serialize;
+
+use crate::{
+    death_cause::DeathCause,
+    direction::Direction,
+    food::FoodKind,
+    point::Point
+};
+
+// What happens during a game, in the order it happens. Everything is about the player's snake
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum GameEvent {
+    GameStarted,
+    // A new tact begins; `elapsed_time` is the game time so far in milliseconds
+    Tick {
+        tick_count: u64,
+        elapsed_time: u64
+    },
+    DirectionChanged {
+        from: Direction,
+        to: Direction
+    },
+    SnakeMoved {
+        head: Point
+    },
+    // Any kind of food, not just apples
+    AppleSpawned {
+        kind: FoodKind,
+        position: Point
+    },
+    AppleEaten {
+        kind: FoodKind
+    },
+    SnakeGrew {
+        length: usize
+    },
+    // The head is right in front of a wall or the border, one more step and it's over
+    NearMiss,
+    SnakeDied {
+        cause: DeathCause
+    },
+    // The snake covers the whole board, the hardest way to win
+    BoardCleared,
+    Victory,
+    Paused {
+        is_paused: bool
+    }
+}
+
+// Anything following the game: stats, achievements, sound, logging, agents over the network...
+pub trait Observer {
+    fn on_event(&mut self, event: &GameEvent);
+}
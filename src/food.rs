@@ -0,0 +1,143 @@
+use rand::{Rng, seq::IndexedRandom};
+use serde::{Deserialize, Serialize};
+
+use crate::point::Point;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FoodKind {
+    Apple,
+    // Worth bonus score, but rots after a while
+    Golden,
+    // Shrinks the snake
+    Poison,
+    // Slows the game down or speeds it up for a while
+    Speed
+}
+
+impl FoodKind {
+    // Whether snakes should go for it
+    pub fn is_edible(&self) -> bool {
+        *self != FoodKind::Poison
+    }
+
+    pub fn get_glyph(&self) -> char {
+        match *self {
+            FoodKind::Apple => '$',
+            FoodKind::Golden => '%',
+            FoodKind::Poison => '!',
+            FoodKind::Speed => '>'
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct Food {
+    kind: FoodKind,
+    position: Point,
+    // Tick at which the food disappears from the board
+    expires_at: Option<u64>
+}
+
+impl Food {
+    pub fn new(kind: FoodKind, position: Point, expires_at: Option<u64>) -> Food {
+        Food { kind, position, expires_at }
+    }
+
+    pub fn get_kind(&self) -> FoodKind {
+        self.kind
+    }
+
+    pub fn get_position(&self) -> Point {
+        self.position
+    }
+
+    pub fn get_expires_at(&self) -> Option<u64> {
+        self.expires_at
+    }
+
+    pub fn has_expired(&self, tick_count: u64) -> bool {
+        self.expires_at.is_some_and(|expires_at| tick_count >= expires_at)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct FoodConfig {
+    count: u32,
+    golden_bonus: u32,
+    golden_lifetime: u64,
+    poison_shrink: usize,
+    speed_effect_ticks: u64,
+    speed_factor: f64,
+    weights: FoodWeights
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct FoodWeights {
+    apple: u32,
+    golden: u32,
+    poison: u32,
+    speed: u32
+}
+
+impl Default for FoodConfig {
+    fn default() -> FoodConfig {
+        FoodConfig {
+            count: 1,
+            golden_bonus: 5,
+            golden_lifetime: 60,
+            poison_shrink: 3,
+            speed_effect_ticks: 50,
+            speed_factor: 2.0,
+            weights: FoodWeights::default()
+        }
+    }
+}
+
+impl Default for FoodWeights {
+    fn default() -> FoodWeights {
+        FoodWeights { apple: 1, golden: 0, poison: 0, speed: 0 }
+    }
+}
+
+impl FoodConfig {
+    pub fn get_count(&self) -> u32 {
+        self.count
+    }
+
+    pub fn get_golden_bonus(&self) -> u32 {
+        self.golden_bonus
+    }
+
+    pub fn get_golden_lifetime(&self) -> u64 {
+        self.golden_lifetime
+    }
+
+    pub fn get_poison_shrink(&self) -> usize {
+        self.poison_shrink
+    }
+
+    pub fn get_speed_effect_ticks(&self) -> u64 {
+        self.speed_effect_ticks
+    }
+
+    pub fn get_speed_factor(&self) -> f64 {
+        self.speed_factor
+    }
+
+    // Picks the kind of the next food according to the spawn weights
+    pub fn pick_kind(&self, rng: &mut impl Rng) -> FoodKind {
+        let weights = [
+            (FoodKind::Apple, self.weights.apple),
+            (FoodKind::Golden, self.weights.golden),
+            (FoodKind::Poison, self.weights.poison),
+            (FoodKind::Speed, self.weights.speed)
+        ];
+
+        weights.choose_weighted(rng, |(_, weight)| *weight)
+            .map(|(kind, _)| *kind)
+            .unwrap_or(FoodKind::Apple)
+    }
+}
@@ -1,115 +1,467 @@
-use std::io::{Write, stdout};
+use std::collections::VecDeque;
 use std::{io, thread, time};
+use std::time::{Duration, Instant};
 
-use crossterm::{
-    event::{KeyCode, KeyEvent},
-    style::Stylize
+use crossterm::event::{KeyCode, KeyEvent};
+use rand::{
+    Rng,
+    SeedableRng,
+    seq::SliceRandom
 };
-use rand::seq::SliceRandom;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
 
 use crate::{
     board::Board,
-    direction::Direction,
+    direction::{Direction, DIRECTIONS},
     snake::{
         Snake,
         SNAKE_INIT_SIZE
     },
-    point::{
-        Point,
-        generate_point
-    },
+    point::{self, Point},
     terminal_handler,
     config::Config,
-    maze
+    controller::{self, Controller, ControllerKind, GameView},
+    death_cause::DeathCause,
+    food::{Food, FoodKind},
+    power_up::{Effect, Effects, PowerUp},
+    ai,
+    hamiltonian::HamiltonianCycle,
+    level::Level,
+    maze::{self, Portal},
+    maze_shifter::MazeShifter,
+    game_mode::GameMode,
+    render_mode::RenderMode,
+    theme::{Look, Theme},
+    palette::ColorSupport,
+    vision::{self, Vision, Visibility},
+    rewind::Rewind,
+    ghost::{Ghost, Recording},
+    stats::{GameStats, StatsStore},
+    achievements::AchievementTracker,
+    events::{GameEvent, Observer},
+    logger::{LogLevel, Logger},
+    cues::CuePlayer,
+    renderer::{self, Frame, PlainRenderer, Renderer, TerminalRenderer},
+    storage
 };
 
+// How many cells in front of every snake's head are kept clear of rising walls
+const FRONT_CLEARANCE: i32 = 2;
+
+// How many of the last tacts are replayed slowly after death, and how slowly
+const REPLAY_FRAMES: usize = 10;
+const REPLAY_PAUSE_TIME: u32 = 250;
+
+// Food comes from its own random stream, so the same seed gives the same foods however the game is played
+const FOOD_RNG_STREAM: u64 = 1;
+
+// Where a game saved with X waits to be resumed with `--resume`
+const SAVE_FILENAME: &str = "save.json";
+
+// What changes from tact to tact, kept to step back to in practice games
+#[derive(Clone)]
+struct Snapshot {
+    snakes: Vec<Snake>,
+    controller_kinds: Vec<ControllerKind>,
+    // Only kept when the maze shifts, otherwise it never changes
+    maze: Option<Option<Vec<Vec<bool>>>>,
+    maze_shifter: MazeShifter,
+    foods: Vec<Food>,
+    power_ups: Vec<PowerUp>,
+    effects: Effects,
+    score: u32,
+    // Rewind penalty paid so far, so the penalties paid since aren't given back
+    rewind_penalty: u32,
+    stats: GameStats,
+    tick_count: u64,
+    elapsed_time: u64,
+    last_meal_tick: u64,
+    rng: ChaCha8Rng,
+    food_rng: ChaCha8Rng
+}
+
+// Wall-clock measurements of the last frame and tact, for the debug overlay and the log
+#[derive(Default)]
+struct Timing {
+    render_time: Duration,
+    tick_interval: Duration,
+    last_tick_at: Option<Instant>
+}
+
+// Everything that can't be saved is rebuilt from the rest once the game is loaded
+#[derive(Serialize, Deserialize)]
 pub struct Game {
     config: Config,
     board: Board,
-    snake: Snake,
+    // The player's snake always comes first, opponents follow
+    snakes: Vec<Snake>,
+    // Which kind each controller is, so they can be built again for a loaded game
+    controller_kinds: Vec<ControllerKind>,
+    #[serde(skip)]
+    controllers: Vec<Box<dyn Controller>>,
     maze: Option<Vec<Vec<bool>>>,
-    has_user_lost: bool,
-    apple: Option<Point>,
-    has_vertical_debt: bool,
-    has_user_quit: bool
+    portals: Vec<Portal>,
+    // Both ends of a portal show the same glyph
+    portal_glyphs: Vec<char>,
+    maze_shifter: MazeShifter,
+    vision: Vision,
+    #[serde(skip)]
+    theme: Theme,
+    death_cause: Option<DeathCause>,
+    // The cell the player died at, shown on the game over screen
+    death_point: Option<Point>,
+    has_user_won: bool,
+    foods: Vec<Food>,
+    score: u32,
+    tick_count: u64,
+    // Game time in milliseconds, the sum of all pauses between tacts so far
+    elapsed_time: u64,
+    last_meal_tick: u64,
+    has_time_run_out: bool,
+    power_ups: Vec<PowerUp>,
+    effects: Effects,
+    #[serde(skip)]
+    has_user_quit: bool,
+    #[serde(skip)]
+    has_user_saved: bool,
+    // Daily and campaign games only count when played through, a resumed one would be lost to them
+    #[serde(skip)]
+    is_saving_disabled: bool,
+    #[serde(skip)]
+    hint_cycle: Option<HamiltonianCycle>,
+    // Counts the maze's changes, so controllers don't have to compare it every tact
+    #[serde(skip)]
+    maze_version: u64,
+    #[serde(skip)]
+    is_hint_shown: bool,
+    // Shown above the score, e.g. the campaign level
+    title: Option<String>,
+    #[serde(skip)]
+    recent_frames: VecDeque<Frame>,
+    // Only practice games have one
+    #[serde(skip)]
+    rewind: Option<Rewind<Snapshot>>,
+    // The game stays on the moment rewound to until the player picks a direction
+    #[serde(skip)]
+    is_rewinding: bool,
+    // A key read while skipping the repeats of R, handled on the next tact
+    #[serde(skip)]
+    pending_key: Option<KeyEvent>,
+    #[serde(skip)]
+    rewind_penalty: u32,
+    // The player's inputs so far, to be raced against as a ghost later
+    recording: Recording,
+    #[serde(skip)]
+    ghost: Option<Ghost>,
+    // Observers too, but kept as fields rather than boxed in `observers`: their counters are
+    // saved with the game and restored on rewind, which a `dyn Observer` can't be
+    stats: GameStats,
+    achievements: AchievementTracker,
+    // Followers of the game from outside, told about every event after stats and achievements.
+    // The HUD isn't one, it's drawn from the game's state every frame
+    #[serde(skip)]
+    observers: Vec<Box<dyn Observer>>,
+    #[serde(skip)]
+    is_paused: bool,
+    // The game's random state comes from it, so it's all it takes to reproduce the board
+    seed: u64,
+    #[serde(skip)]
+    logger: Option<Logger>,
+    #[serde(skip)]
+    is_debug_shown: bool,
+    #[serde(skip)]
+    timing: Timing,
+    #[serde(skip, default = "renderer::default_renderer")]
+    renderer: Box<dyn Renderer>,
+    rng: ChaCha8Rng,
+    food_rng: ChaCha8Rng
 }
 
 impl Game {
-    pub fn basic() -> Game {
-        Game::new(8, 8)
+    pub fn new(config: Config) -> Game {
+        Game::with_seed(config, rand::random())
     }
     
-    pub fn new(width: u32, height: u32) -> Game {
-        let config = Config::read();
+    // The same seed and config always produce the same maze, spawns and apples
+    pub fn with_seed(config: Config, seed: u64) -> Game {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut food_rng = ChaCha8Rng::seed_from_u64(seed);
+        food_rng.set_stream(FOOD_RNG_STREAM);
+        let level = config.get_level_file().map(Level::read);
+        let (width, height) = match &level {
+            Some(level) => (level.get_width(), level.get_height()),
+            None => (config.get_width(), config.get_height())
+        };
         
-        let board = Board::new(config.get_width(), config.get_height())
-            .expect(
-                &format!("Invalid Board configuration: ({width}, {height})")
+        let board = Board::new(width, height)
+            .unwrap_or_else(
+                || panic!("Invalid Board configuration: ({width}, {height})")
             );
         let center = board.get_center();
         
-        let maze: Option<Vec<Vec<bool>>>;
-        let snake: Snake;
+        let maze = match &level {
+            Some(level) => Some(level.get_maze().to_vec()),
+            None if config.is_maze_enabled() => Some(maze::build_maze(&board, config.get_maze_freedom(), &mut rng)),
+            None => None
+        };
+        let (mut portals, mut portal_glyphs) = match &level {
+            Some(level) => (level.get_portals().to_vec(), level.get_portal_glyphs().to_vec()),
+            None => (vec![], vec![])
+        };
         
-        if config.is_maze_enabled() {
-            let actual_maze = maze::build_maze(&board, config.get_maze_freedom());
-            snake = Game::generate_snake_spawn_between_maze(&actual_maze, &board);
-            maze = Some(actual_maze);
-        } else {
-            maze = None;
-            snake = Snake::from_center(center);
+        let mut snakes = vec![];
+        let player = match &maze {
+            Some(actual_maze) => {
+                let blocked_cells = Game::build_blocked_cells(Some(actual_maze), &snakes, &portals, &board);
+                Game::generate_snake_spawn(&blocked_cells, &board, &mut rng)
+            }
+            None => Snake::from_center(center)
+        };
+        
+        if config.get_level_file().is_none() && let Some(actual_maze) = &maze {
+            // Keeping the cell in front of the snake clear, so it doesn't teleport right away
+            let head = player.get_head();
+            let occupied: Vec<Point> = player.get_segments().iter()
+                .cloned()
+                .chain(std::iter::once(head.moved(player.get_direction())))
+                .collect();
+            portals = maze::place_portals(actual_maze, &board, config.get_portal_pairs(), *head, &occupied, &mut rng);
+            portal_glyphs = (0..portals.len()).map(Game::get_portal_glyph).collect();
+        }
+        snakes.push(player);
+        
+        for _ in config.get_opponents() {
+            let blocked_cells = Game::build_blocked_cells(maze.as_deref(), &snakes, &portals, &board);
+            snakes.push(Game::generate_snake_spawn(&blocked_cells, &board, &mut rng));
         }
         
+        if config.has_vertical_debt() {
+            snakes.iter_mut().for_each(Snake::enable_vertical_debt);
+        }
+        
+        let controller_kinds = std::iter::once(config.get_controller())
+            .chain(config.get_opponents().iter().map(|opponent| opponent.get_controller()))
+            .collect();
+        
+        let maze_shifter = MazeShifter::new(config.get_shifting_maze());
+        let vision = Vision::new(config.get_vision(), &board);
+        let maze_freedom = (config.get_level_file().is_none() && maze.is_some()).then(|| config.get_maze_freedom());
+        let achievements = AchievementTracker::new(board.get_width() * board.get_height(), maze_freedom);
+        
         let mut game = Game {
             config,
             board,
-            snake,
+            snakes,
+            controller_kinds,
+            controllers: vec![],
             maze,
-            has_user_lost: false,
-            apple: None,
-            has_vertical_debt: true,
-            has_user_quit: false
+            portals,
+            portal_glyphs,
+            maze_shifter,
+            vision,
+            theme: Theme::default(),
+            death_cause: None,
+            death_point: None,
+            has_user_won: false,
+            foods: vec![],
+            score: 0,
+            tick_count: 0,
+            elapsed_time: 0,
+            last_meal_tick: 0,
+            has_time_run_out: false,
+            power_ups: vec![],
+            effects: Effects::default(),
+            has_user_quit: false,
+            has_user_saved: false,
+            is_saving_disabled: false,
+            hint_cycle: None,
+            maze_version: 0,
+            is_hint_shown: false,
+            title: None,
+            recent_frames: VecDeque::new(),
+            rewind: None,
+            is_rewinding: false,
+            pending_key: None,
+            rewind_penalty: 0,
+            recording: Recording::default(),
+            ghost: None,
+            stats: GameStats::default(),
+            achievements,
+            observers: vec![],
+            is_paused: false,
+            seed,
+            logger: None,
+            is_debug_shown: false,
+            timing: Timing::default(),
+            renderer: renderer::default_renderer(),
+            rng,
+            food_rng
         };
-        game.generate_apple();
+        game.rebuild();
+        game.generate_foods();
+        game.vision.update(game.maze.as_deref(), game.snakes[0].get_head());
         
         game
     }
     
-    fn generate_snake_spawn_between_maze(maze: &Vec<Vec<bool>>, board: &Board) -> Snake {
-        let suffix_free_count = Game::build_suffix_free_count(maze, board);
-        let mut rng = rand::rng();
+    // The game saved with X, if there's one. The save is used up, so the game can't be replayed from it
+    pub fn resume() -> io::Result<Option<Game>> {
+        Game::resume_from(SAVE_FILENAME)
+    }
+    
+    fn resume_from(filename: &str) -> io::Result<Option<Game>> {
+        let game: Option<Game> = storage::load(filename);
+        if game.is_some() {
+            storage::remove(filename)?;
+        }
+        
+        Ok(game.map(|mut game| {
+            game.rebuild();
+            game
+        }))
+    }
+    
+    // Builds the parts of the game that aren't saved
+    fn rebuild(&mut self) {
+        self.controllers = self.controller_kinds.iter()
+            .copied()
+            .map(controller::build_controller)
+            .collect();
+        
+        // Portals would throw the snake off the cycle
+        self.hint_cycle = if self.portals.is_empty() {
+            HamiltonianCycle::build(&self.board, self.maze.as_deref())
+        } else {
+            None
+        };
+        
+        self.theme = self.config.get_theme().map(Theme::load).unwrap_or_default()
+            .with_palette(self.config.get_palette())
+            .with_color_support(ColorSupport::detect());
+        
+        self.rewind = self.config.is_practice().then(|| Rewind::new(self.config.get_rewind()));
+    }
+    
+    fn take_snapshot(&self) -> Snapshot {
+        Snapshot {
+            snakes: self.snakes.clone(),
+            controller_kinds: self.controller_kinds.clone(),
+            maze: self.config.get_shifting_maze().is_enabled().then(|| self.maze.clone()),
+            maze_shifter: self.maze_shifter.clone(),
+            foods: self.foods.clone(),
+            power_ups: self.power_ups.clone(),
+            effects: self.effects.clone(),
+            score: self.score,
+            rewind_penalty: self.rewind_penalty,
+            stats: self.stats.clone(),
+            tick_count: self.tick_count,
+            elapsed_time: self.elapsed_time,
+            last_meal_tick: self.last_meal_tick,
+            rng: self.rng.clone(),
+            food_rng: self.food_rng.clone()
+        }
+    }
+    
+    fn can_rewind(&self) -> bool {
+        self.rewind.as_ref().is_some_and(|rewind| !rewind.is_empty())
+    }
+    
+    // Steps one tact back. Every rewind costs a penalty once, however far back it goes
+    fn rewind(&mut self) {
+        let Some(snapshot) = self.rewind.as_mut().and_then(Rewind::step_back) else {
+            return;
+        };
+        
+        if !self.is_rewinding {
+            self.is_rewinding = true;
+            self.rewind_penalty += self.config.get_rewind().get_penalty();
+        }
+        
+        if snapshot.controller_kinds.len() != self.controller_kinds.len() {
+            self.controllers = snapshot.controller_kinds.iter()
+                .copied()
+                .map(controller::build_controller)
+                .collect();
+        }
+        if let Some(maze) = snapshot.maze {
+            self.maze = maze;
+            self.maze_version += 1;
+            if self.portals.is_empty() {
+                self.hint_cycle = HamiltonianCycle::build(&self.board, self.maze.as_deref());
+            }
+        }
+        
+        self.snakes = snapshot.snakes;
+        self.controller_kinds = snapshot.controller_kinds;
+        self.maze_shifter = snapshot.maze_shifter;
+        self.foods = snapshot.foods;
+        self.power_ups = snapshot.power_ups;
+        self.effects = snapshot.effects;
+        self.score = snapshot.score.saturating_sub(self.rewind_penalty - snapshot.rewind_penalty);
+        self.stats = snapshot.stats;
+        self.tick_count = snapshot.tick_count;
+        self.elapsed_time = snapshot.elapsed_time;
+        self.last_meal_tick = snapshot.last_meal_tick;
+        self.rng = snapshot.rng;
+        self.food_rng = snapshot.food_rng;
+        
+        self.death_cause = None;
+        self.death_point = None;
+        // The replay after death shouldn't show moments that never happened
+        self.recent_frames.clear();
+        self.vision.update(self.maze.as_deref(), self.snakes[0].get_head());
+    }
+    
+    fn build_blocked_cells(maze: Option<&[Vec<bool>]>, snakes: &[Snake], portals: &[Portal],
+            board: &Board) -> Vec<Vec<bool>> {
+        let mut blocked_cells = match maze {
+            Some(maze) => maze.to_vec(),
+            None => vec![vec![false; board.get_width()]; board.get_height()]
+        };
+        
+        let portal_ends = portals.iter().flat_map(|(entrance, exit)| [entrance, exit]);
+        for point in snakes.iter().flat_map(|snake| snake.get_segments()).chain(portal_ends) {
+            blocked_cells[point.get_y() as usize][point.get_x() as usize] = true;
+        }
+        
+        blocked_cells
+    }
+    
+    fn generate_snake_spawn(blocked_cells: &[Vec<bool>], board: &Board, rng: &mut ChaCha8Rng) -> Snake {
+        let suffix_free_count = Game::build_suffix_free_count(blocked_cells, board);
         let mut suitable_cells: Vec<Point> = vec![];
         
-        for i in 0..board.get_height() {
-            for j in 0..board.get_width() {
-                if suffix_free_count[i][j] >= 4 {
+        for (i, row) in suffix_free_count.iter().enumerate() {
+            for (j, free_count) in row.iter().enumerate() {
+                if *free_count >= 4 {
                     suitable_cells.push(Point::new(j as i32, i as i32));
                 }
             }
         }
         
-        suitable_cells.shuffle(&mut rng);
+        suitable_cells.shuffle(rng);
         
-        let tail = suitable_cells.get(0)
+        let tail = suitable_cells.first()
             .expect("Couldn't spawn a snake because of too tight maze. Try changing your configuration");
         
         Snake::from_segments(
             vec![
                 Point::new(tail.get_x() + 1, tail.get_y()),
-                tail.clone()
+                *tail
             ]
         )
     }
     
-    fn build_suffix_free_count(maze: &Vec<Vec<bool>>, board: &Board) -> Vec<Vec<u32>> {
-        let maze = maze;
+    fn build_suffix_free_count(maze: &[Vec<bool>], board: &Board) -> Vec<Vec<u32>> {
         let mut suffix_free_count = vec![vec![0; board.get_width()]; board.get_height()];
         
-        for i in 0..board.get_height() {
+        for (i, row) in suffix_free_count.iter_mut().enumerate() {
             for j in (0..board.get_width()).rev() {
                 if !maze[i][j] {
-                    let in_front_cell_count = suffix_free_count[i].get(j + 1).cloned().unwrap_or(0);
-                    suffix_free_count[i][j] = in_front_cell_count + 1;
+                    let in_front_cell_count = row.get(j + 1).cloned().unwrap_or(0);
+                    row[j] = in_front_cell_count + 1;
                 }
             }
         }
@@ -121,54 +473,477 @@ impl Game {
         thread::sleep(time::Duration::from_millis(millis as u64));
     }
     
-    fn move_snake_forward(&mut self) {
-        if self.snake.get_direction().is_vertical() {
-            if self.has_vertical_debt {
-                self.has_vertical_debt = false;
+    pub fn calculate_user_score(&self) -> u32 {
+        self.score
+    }
+    
+    // Pause between tacts for the current score, according to the difficulty curve
+    // and whatever effects slow the game down or speed it up
+    fn get_pause_time(&self) -> u32 {
+        let pause_time = self.config.get_difficulty()
+            .get_pause_time(self.config.get_pause_time(), self.calculate_user_score());
+        
+        (pause_time as f64 * self.effects.get_pause_multiplier()).round() as u32
+    }
+    
+    // Advances the simulation by one step, independent of the terminal and timing
+    pub fn tick(&mut self, pressed_direction: Option<Direction>) {
+        if self.rewind.is_some() {
+            let snapshot = self.take_snapshot();
+            if let Some(rewind) = &mut self.rewind {
+                rewind.record(self.elapsed_time, snapshot);
+            }
+        }
+        
+        if let Some(direction) = pressed_direction {
+            self.recording.record(self.tick_count, direction);
+        }
+        
+        self.tick_count += 1;
+        self.elapsed_time += self.get_pause_time() as u64;
+        self.publish(GameEvent::Tick { tick_count: self.tick_count, elapsed_time: self.elapsed_time });
+        
+        self.effects.expire(self.tick_count);
+        
+        let player = &self.snakes[0];
+        let (direction, head, len) = (player.get_direction(), *player.get_head(), player.get_len());
+        self.steer_snakes(pressed_direction);
+        if self.effects.is_active(Effect::Magnet) {
+            self.pull_foods_to_player();
+        }
+        self.move_snakes();
+        self.publish_player_changes(direction, head, len);
+        self.apply_mode_rules();
+        self.check_target_length();
+        self.shift_maze();
+        self.generate_foods();
+        self.generate_power_ups();
+        self.vision.update(self.maze.as_deref(), self.snakes[0].get_head());
+        
+        if let Some(ghost) = &mut self.ghost {
+            ghost.tick();
+        }
+        
+        if let Some(cause) = self.death_cause {
+            self.publish(GameEvent::SnakeDied { cause });
+        }
+        if self.has_user_won {
+            self.publish(GameEvent::Victory);
+        }
+    }
+    
+    // Tells what the move did to the player, given how the snake was before it
+    fn publish_player_changes(&mut self, direction: Direction, head: Point, len: usize) {
+        let player = &self.snakes[0];
+        let (new_direction, new_head, new_len) = (player.get_direction(), *player.get_head(), player.get_len());
+        
+        // A snake stopped by zen mode hasn't turned either
+        if new_head == head {
+            return;
+        }
+        if new_direction != direction {
+            self.publish(GameEvent::DirectionChanged { from: direction, to: new_direction });
+        }
+        self.publish(GameEvent::SnakeMoved { head: new_head });
+        if new_len > len {
+            self.publish(GameEvent::SnakeGrew { length: new_len });
+        }
+        if self.is_player_facing_wall() {
+            self.publish(GameEvent::NearMiss);
+        }
+    }
+    
+    // Walls don't count while the player can pass through them
+    fn is_player_facing_wall(&self) -> bool {
+        let player = &self.snakes[0];
+        let view = self.build_view(None);
+        if self.death_cause.is_some() || !view.is_inside(player.get_head()) {
+            return false;
+        }
+        
+        let ahead = view.step(player.get_head(), player.get_direction());
+        !view.is_inside(&ahead) || (view.is_wall(&ahead) && !self.effects.is_active(Effect::Ghost))
+    }
+    
+    pub fn subscribe(&mut self, observer: Box<dyn Observer>) {
+        self.observers.push(observer);
+    }
+    
+    fn publish(&mut self, event: GameEvent) {
+        self.stats.on_event(&event);
+        self.achievements.on_event(&event);
+        for observer in &mut self.observers {
+            observer.on_event(&event);
+        }
+    }
+    
+    pub fn is_over(&self) -> bool {
+        self.death_cause.is_some() || self.has_user_won || self.has_time_run_out
+    }
+    
+    pub fn has_user_won(&self) -> bool {
+        self.has_user_won
+    }
+    
+    pub fn get_death_cause(&self) -> Option<DeathCause> {
+        self.death_cause
+    }
+    
+    pub fn get_tick_count(&self) -> u64 {
+        self.tick_count
+    }
+    
+    pub fn get_recording(&self) -> &Recording {
+        &self.recording
+    }
+    
+    // Races the player against `ghost` from the start of the game
+    pub fn set_ghost(&mut self, ghost: Ghost) {
+        self.ghost = Some(ghost);
+    }
+    
+    pub fn set_title(&mut self, title: String) {
+        self.title = Some(title);
+    }
+    
+    pub fn disable_saving(&mut self) {
+        self.is_saving_disabled = true;
+    }
+    
+    pub fn has_user_saved(&self) -> bool {
+        self.has_user_saved
+    }
+    
+    pub fn get_player(&self) -> &Snake {
+        &self.snakes[0]
+    }
+    
+    pub fn get_foods(&self) -> &[Food] {
+        &self.foods
+    }
+    
+    // Every effect the player has, with how many ticks it has left
+    pub fn get_effects(&self) -> Vec<(Effect, u64)> {
+        self.effects.get_remaining(self.tick_count)
+    }
+    
+    fn get_edible_positions(foods: &[Food]) -> Vec<Point> {
+        foods.iter()
+            .filter(|food| food.get_kind().is_edible())
+            .map(|food| food.get_position())
+            .collect()
+    }
+    
+    fn build_view(&self, pressed_direction: Option<Direction>) -> GameView<'_> {
+        let edible_foods = Game::get_edible_positions(&self.foods);
+        GameView::new(&self.board, self.maze.as_deref(), self.maze_version, &self.portals, edible_foods, &self.snakes,
+            pressed_direction)
+    }
+    
+    fn steer_snakes(&mut self, pressed_direction: Option<Direction>) {
+        // Built from the fields directly, as the controllers are borrowed mutably alongside
+        let edible_foods = Game::get_edible_positions(&self.foods);
+        let view = GameView::new(&self.board, self.maze.as_deref(), self.maze_version, &self.portals, edible_foods,
+            &self.snakes, pressed_direction);
+        
+        let directions: Vec<Option<Direction>> = self.controllers.iter_mut()
+            .enumerate()
+            .map(|(index, controller)| controller.next_direction(&view, index))
+            .collect();
+        
+        for (snake, direction) in self.snakes.iter_mut().zip(directions) {
+            if let Some(direction) = direction {
+                snake.change_direction(direction);
+            }
+        }
+    }
+    
+    fn move_snakes(&mut self) {
+        let player_before_move = self.snakes[0].clone();
+        let mut player_meal = None;
+        
+        for index in 0..self.snakes.len() {
+            let head = *self.snakes[index].get_head();
+            
+            match self.foods.iter().position(|food| food.get_position() == head) {
+                Some(food_index) => {
+                    let kind = self.foods[food_index].get_kind();
+                    self.feed_snake(index, kind);
+                    if index == 0 {
+                        player_meal = Some(head);
+                    } else {
+                        self.foods.remove(food_index);
+                    }
+                }
+                None if self.config.get_mode() == GameMode::Tron => self.snakes[index].stretch(),
+                None => self.snakes[index].advance()
+            }
+            
+            // A snake that stayed put this tact is already standing on the portal's exit
+            if *self.snakes[index].get_head() != head {
+                self.go_through_portal(index);
+            }
+        }
+        
+        self.pick_up_power_up();
+        let is_player_move_undone = self.validate_snakes(player_before_move);
+        
+        // A move that didn't happen didn't eat anything either, the food stays for the next try
+        if let Some(position) = player_meal && !is_player_move_undone
+                && let Some(food_index) = self.foods.iter().position(|food| food.get_position() == position) {
+            let food = self.foods.remove(food_index);
+            self.eat_food(food.get_kind());
+        }
+    }
+    
+    fn apply_mode_rules(&mut self) {
+        if self.death_cause.is_some() {
+            return;
+        }
+        
+        match self.config.get_mode() {
+            GameMode::TimeAttack { duration } => {
+                if self.elapsed_time >= duration * 1000 {
+                    self.has_time_run_out = true;
+                }
+            }
+            GameMode::Survival { hunger_ticks, .. } => {
+                if self.tick_count - self.last_meal_tick >= hunger_ticks {
+                    self.last_meal_tick = self.tick_count;
+                    
+                    if self.snakes[0].get_len() <= SNAKE_INIT_SIZE as usize {
+                        self.death_cause = Some(DeathCause::Starvation);
+                        self.death_point = Some(*self.snakes[0].get_head());
+                    } else {
+                        self.snakes[0].shrink(1, SNAKE_INIT_SIZE as usize);
+                    }
+                }
+            }
+            GameMode::Tron => {
+                self.score += 1;
+                // The last snake standing wins
+                if self.snakes.len() == 1 && !self.config.get_opponents().is_empty() {
+                    self.has_user_won = true;
+                }
+            }
+            GameMode::Classic | GameMode::Zen => {}
+        }
+    }
+    
+    // Growing long enough clears the board, if there's a target at all
+    fn check_target_length(&mut self) {
+        if self.death_cause.is_none() && let Some(target_length) = self.config.get_target_length()
+                && self.snakes[0].get_len() >= target_length {
+            self.has_user_won = true;
+        }
+    }
+    
+    fn shift_maze(&mut self) {
+        if self.maze.is_none() {
+            return;
+        }
+        
+        let mut protected: Vec<Point> = vec![];
+        for snake in &self.snakes {
+            protected.extend(snake.get_segments());
+            
+            let mut cell = *snake.get_head();
+            for _ in 0..FRONT_CLEARANCE {
+                cell = cell.moved(snake.get_direction());
+                protected.push(cell);
+            }
+        }
+        protected.extend(self.foods.iter().map(|food| food.get_position()));
+        protected.extend(self.power_ups.iter().map(|power_up| power_up.get_position()));
+        protected.extend(self.portals.iter().flat_map(|(entrance, exit)| [*entrance, *exit]));
+        
+        let origin = *self.snakes[0].get_head();
+        let targets = Game::get_edible_positions(&self.foods);
+        let maze = self.maze.as_mut().expect("Checked above");
+        
+        let has_changed = self.maze_shifter.update(maze, self.tick_count, &protected, origin, &targets, &mut self.rng);
+        
+        if has_changed {
+            self.maze_version += 1;
+        }
+        if has_changed && self.portals.is_empty() {
+            self.hint_cycle = HamiltonianCycle::build(&self.board, self.maze.as_deref());
+        }
+    }
+    
+    fn go_through_portal(&mut self, snake_index: usize) {
+        let head = *self.snakes[snake_index].get_head();
+        
+        for (entrance, exit) in &self.portals {
+            if head == *entrance {
+                self.snakes[snake_index].teleport_head(*exit);
+            } else if head == *exit {
+                self.snakes[snake_index].teleport_head(*entrance);
             } else {
-                self.has_vertical_debt = true;
-                self.snake.move_forward();
+                continue;
+            }
+            return;
+        }
+    }
+    
+    fn is_portal(&self, point: &Point) -> bool {
+        self.portals.iter().any(|(entrance, exit)| entrance == point || exit == point)
+    }
+    
+    fn pick_up_power_up(&mut self) {
+        let head = *self.snakes[0].get_head();
+        
+        if let Some(index) = self.power_ups.iter().position(|power_up| power_up.get_position() == head) {
+            let power_up = self.power_ups.remove(index);
+            let expires_at = self.tick_count + self.config.get_power_ups().get_duration();
+            self.effects.activate(power_up.get_kind().get_effect(), expires_at);
+        }
+    }
+    
+    // Every edible food near the head moves a cell closer to it, if there's room
+    fn pull_foods_to_player(&mut self) {
+        let head = *self.snakes[0].get_head();
+        let radius = self.config.get_power_ups().get_magnet_radius();
+        
+        for index in 0..self.foods.len() {
+            let food = self.foods[index];
+            let position = food.get_position();
+            
+            if !food.get_kind().is_edible() || position.distance_to(&head) > radius {
+                continue;
+            }
+            
+            let target = DIRECTIONS.into_iter()
+                .map(|direction| position.moved(direction))
+                .filter(|target| target.distance_to(&head) < position.distance_to(&head))
+                .find(|target| self.is_cell_free(target));
+            
+            if let Some(target) = target {
+                self.foods[index] = Food::new(food.get_kind(), target, food.get_expires_at());
             }
-        } else {
-            self.has_vertical_debt = false;
-            self.snake.move_forward();
         }
-        self.validate_snake();
     }
     
-    fn calculate_user_score(&self) -> u32 {
-        (self.snake.get_segments().len() as u32).saturating_sub(SNAKE_INIT_SIZE)
+    // Nothing lies on the cell: no wall, snake, portal, food or power-up
+    fn is_cell_free(&self, point: &Point) -> bool {
+        !self.build_view(None).is_blocked(point) &&
+            !self.is_portal(point) &&
+            self.foods.iter().all(|food| food.get_position() != *point) &&
+            self.power_ups.iter().all(|power_up| power_up.get_position() != *point)
+    }
+    
+    // Any snake grows from food, or shrinks from poison, as it moves on
+    fn feed_snake(&mut self, snake_index: usize, kind: FoodKind) {
+        let snake = &mut self.snakes[snake_index];
+        
+        match kind {
+            FoodKind::Apple | FoodKind::Golden | FoodKind::Speed => snake.grow(),
+            FoodKind::Poison => {
+                snake.advance();
+                snake.shrink(self.config.get_food().get_poison_shrink(), SNAKE_INIT_SIZE as usize);
+            }
+        }
+    }
+    
+    // Only the player scores and gets speed effects
+    fn eat_food(&mut self, kind: FoodKind) {
+        self.publish(GameEvent::AppleEaten { kind });
+        if kind.is_edible() {
+            self.last_meal_tick = self.tick_count;
+        }
+        let food_config = self.config.get_food();
+        
+        match kind {
+            FoodKind::Apple => self.score += 1,
+            FoodKind::Golden => self.score += food_config.get_golden_bonus(),
+            FoodKind::Poison => {}
+            FoodKind::Speed => {
+                self.score += 1;
+                
+                let factor = food_config.get_speed_factor();
+                let multiplier = if self.rng.random_bool(0.5) { factor } else { 1.0 / factor };
+                self.effects.activate(Effect::Speed(multiplier), self.tick_count + food_config.get_speed_effect_ticks());
+            }
+        }
     }
     
-    fn grow_snake(&mut self) {
-        self.snake.grow();
-        self.validate_snake();
+    // Tells whether the player's move was undone
+    fn validate_snakes(&mut self, player_before_move: Snake) -> bool {
+        let deaths: Vec<(usize, DeathCause)> = (0..self.snakes.len())
+            .filter_map(|index| self.find_death_cause(index).map(|cause| (index, cause)))
+            .collect();
+        let mut is_player_move_undone = false;
+        
+        for (index, cause) in deaths.into_iter().rev() {
+            if index == 0 && self.config.get_mode() == GameMode::Zen {
+                // The move just doesn't happen, until the player turns somewhere free
+                self.snakes[0] = player_before_move.clone();
+                is_player_move_undone = true;
+            } else if index == 0 && self.effects.is_active(Effect::Shield) {
+                self.absorb_collision(player_before_move.clone());
+                is_player_move_undone = true;
+            } else if index == 0 {
+                // Running off the board ends next to the border, where the head last was
+                let head = *self.snakes[0].get_head();
+                self.death_point = Some(if cause == DeathCause::Border { *player_before_move.get_head() } else { head });
+                self.death_cause = Some(cause);
+            } else {
+                self.snakes.remove(index);
+                self.controllers.remove(index);
+                self.controller_kinds.remove(index);
+            }
+        }
+        
+        is_player_move_undone
     }
     
-    fn change_snake_direction(&mut self, direction: Direction) {
-        self.snake.change_direction(direction);
+    // The shield breaks instead of the player: the fatal move is undone
+    // and the snake turns somewhere safe, if there's such a place
+    fn absorb_collision(&mut self, player_before_move: Snake) {
+        self.effects.remove(Effect::Shield);
+        self.snakes[0] = player_before_move;
+        
+        if let Some(direction) = ai::safe_directions(&self.build_view(None), 0).first() {
+            self.snakes[0].change_direction(*direction);
+        }
     }
     
-    fn validate_snake(&mut self) {
-        let head = self.snake.get_head();
+    fn find_death_cause(&self, index: usize) -> Option<DeathCause> {
+        let snake = &self.snakes[index];
+        let head = snake.get_head();
         
-        self.has_user_lost = {
-            head.get_x() >= self.board.get_width() as i32 ||
-            head.get_y() >= self.board.get_height() as i32 ||
-            head.get_x() < 0 || head.get_y() < 0 ||
-            self.snake.is_self_collision() ||
-            self.has_snake_hit_wall()
+        if head.get_x() >= self.board.get_width() as i32 ||
+                head.get_y() >= self.board.get_height() as i32 ||
+                head.get_x() < 0 || head.get_y() < 0 {
+            Some(DeathCause::Border)
+        } else if self.has_snake_hit_wall(snake) && !(index == 0 && self.effects.is_active(Effect::Ghost)) {
+            Some(DeathCause::MazeWall)
+        } else if let Some(segment) = snake.find_bitten_segment() {
+            Some(DeathCause::OwnBody { segment })
+        } else if self.has_snake_hit_other(index) {
+            Some(DeathCause::OtherSnake)
+        } else {
+            None
         }
     }
     
-    fn has_snake_hit_wall(&self) -> bool {
+    fn has_snake_hit_wall(&self, snake: &Snake) -> bool {
         if let Some(maze) = &self.maze {
-            let head = self.snake.get_head();
+            let head = snake.get_head();
             return maze[head.get_y() as usize][head.get_x() as usize];
         }
         false
     }
     
+    fn has_snake_hit_other(&self, index: usize) -> bool {
+        let head = self.snakes[index].get_head();
+        
+        self.snakes.iter()
+            .enumerate()
+            .filter(|(other_index, _)| *other_index != index)
+            .any(|(_, other)| other.get_segments().contains(head))
+    }
+    
     fn enable_game_mode(&self) -> io::Result<()> {
         terminal_handler::enable_raw_mode()?;
         terminal_handler::prepare_screen()?;
@@ -192,15 +967,50 @@ impl Game {
     }
     
     fn get_user_input(&mut self) -> io::Result<Option<Direction>> {
-        let key_event_option = terminal_handler::get_key_event()?;
+        let key_event_option = match self.pending_key.take() {
+            Some(key_event) => Some(key_event),
+            None => terminal_handler::get_key_event()?
+        };
         
         match key_event_option {
             Some(key_event) => {
+                self.log(LogLevel::Debug, "input", json!({ "key": format!("{:?}", key_event.code) }));
+                
                 if key_event.code == KeyCode::Char('q') {
                     self.has_user_quit = true;
                     return Ok(None);
                 }
                 
+                if key_event.code == KeyCode::Char('x') && !self.is_saving_disabled && !self.config.is_practice() {
+                    storage::save(SAVE_FILENAME, self)?;
+                    self.has_user_saved = true;
+                    self.has_user_quit = true;
+                    return Ok(None);
+                }
+                
+                // One step back per tact while R is held, and none once it's released
+                if key_event.code == KeyCode::Char('r') && self.rewind.is_some() {
+                    self.rewind();
+                    self.pending_key = terminal_handler::skip_key_repeats(KeyCode::Char('r'))?;
+                    return Ok(None);
+                }
+                
+                if key_event.code == KeyCode::Char('h') {
+                    self.is_hint_shown = !self.is_hint_shown;
+                    return Ok(None);
+                }
+                
+                if key_event.code == KeyCode::F(3) {
+                    self.is_debug_shown = !self.is_debug_shown;
+                    return Ok(None);
+                }
+                
+                if key_event.code == KeyCode::Char('p') {
+                    self.is_paused = !self.is_paused;
+                    self.publish(GameEvent::Paused { is_paused: self.is_paused });
+                    return Ok(None);
+                }
+                
                 let new_direction = self.get_direction_from_key_event(key_event);
                 Ok(new_direction)
             }
@@ -210,50 +1020,171 @@ impl Game {
         }
     }
 
-    fn generate_apple(&mut self) {
-        if self.apple.is_some() {
+    fn is_board_covered_by_player(&self) -> bool {
+        let player = &self.snakes[0];
+        
+        (0..self.board.get_height()).all(|y| (0..self.board.get_width()).all(|x| {
+            let is_wall = self.maze.as_ref().is_some_and(|maze| maze[y][x]);
+            is_wall || player.get_segments().contains(&Point::new(x as i32, y as i32))
+        }))
+    }
+    
+    fn generate_foods(&mut self) {
+        let tick_count = self.tick_count;
+        self.foods.retain(|food| !food.has_expired(tick_count));
+        
+        let mut free_cells = vec![];
+        for y in 0..self.board.get_height() {
+            for x in 0..self.board.get_width() {
+                let point = Point::new(x as i32, y as i32);
+                
+                if let Some(maze) = &self.maze && maze[y][x] {
+                    continue;
+                }
+                if !self.snakes.iter().any(|snake| snake.get_segments().contains(&point)) {
+                    free_cells.push(point);
+                }
+            }
+        }
+        
+        // No room left: the player has cleared the board only if it's their snake covering it
+        if free_cells.is_empty() {
+            if self.is_board_covered_by_player() {
+                self.has_user_won = true;
+                self.publish(GameEvent::BoardCleared);
+            }
             return;
         }
         
-        loop {
-            let apple_point = generate_point(self.board.get_width() as u32, self.board.get_height() as u32);
-            
-            assert!(apple_point.get_x() >= 0 && apple_point.get_y() >= 0,
-                "Generated point for apple spawning has negative coordinates!");
+        match self.config.get_mode() {
+            GameMode::Tron => return,
+            GameMode::Survival { food_chance, .. } if !self.food_rng.random_bool(food_chance) => return,
+            _ => {}
+        }
+        
+        free_cells.retain(|cell| {
+            self.foods.iter().all(|food| food.get_position() != *cell) && !self.is_portal(cell)
+        });
+        
+        let food_config = self.config.get_food();
+        let mut spawned = vec![];
+        while self.foods.len() < food_config.get_count() as usize && !free_cells.is_empty() {
+            // A taken cell gives way to the next free one, so each food costs the same random draws
+            let wanted = point::generate_point(self.board.get_width() as u32, self.board.get_height() as u32,
+                &mut self.food_rng);
+            let index = free_cells.partition_point(|cell| (cell.get_y(), cell.get_x()) < (wanted.get_y(), wanted.get_x()))
+                % free_cells.len();
+            let cell = free_cells.remove(index);
             
-            let apple_x = apple_point.get_x() as usize;
-            let apple_y = apple_point.get_y() as usize;
+            let kind = food_config.pick_kind(&mut self.food_rng);
+            let expires_at = match kind {
+                FoodKind::Golden => Some(self.tick_count + food_config.get_golden_lifetime()),
+                _ => None
+            };
             
-            if !self.snake.get_segments().contains(&apple_point) {
-                if let Some(maze) = &self.maze && maze[apple_y][apple_x] {
-                    continue;
-                }
-                self.apple = Some(apple_point);
-                break;
-            }
+            self.foods.push(Food::new(kind, cell, expires_at));
+            spawned.push(GameEvent::AppleSpawned { kind, position: cell });
         }
-    }
-    
-    fn is_snake_head_on_apple(&self) -> bool {
-        if let Some(apple_point) = self.apple {
-            return *self.snake.get_head() == apple_point
+        
+        for event in spawned {
+            self.publish(event);
         }
-        false
     }
     
-    fn hide_apple(&mut self) {
-        self.apple = None;
+    fn generate_power_ups(&mut self) {
+        let tick_count = self.tick_count;
+        self.power_ups.retain(|power_up| !power_up.has_expired(tick_count));
+        
+        let power_up_config = self.config.get_power_ups();
+        if !self.power_ups.is_empty() || !self.rng.random_bool(power_up_config.get_chance()) {
+            return;
+        }
+        
+        let Some(kind) = power_up_config.pick_kind(&mut self.rng) else {
+            return;
+        };
+        
+        let position = Point::new(
+            self.rng.random_range(0..self.board.get_width()) as i32,
+            self.rng.random_range(0..self.board.get_height()) as i32
+        );
+        
+        // Rather than hunting for a free cell, just try again on a later tact
+        if self.is_cell_free(&position) {
+            let expires_at = self.tick_count + power_up_config.get_lifetime();
+            self.power_ups.push(PowerUp::new(kind, position, expires_at));
+        }
     }
     
     fn quit(&self) -> io::Result<()> {
         self.disable_game_mode()?;
-        if self.has_user_lost {
-            println!("GAME OVER!");
+        if let Some(cause) = self.death_cause {
+            println!("GAME OVER! You {}", cause.describe());
+        } else if self.has_user_won {
+            println!("YOU WIN!");
+        } else if self.has_time_run_out {
+            println!("TIME'S UP!");
+        } else if self.has_user_saved {
+            println!("Game saved, continue it with `snake --resume`");
+        }
+        println!("{}", self.get_result());
+        println!("{}", self.stats.describe());
+        for achievement in self.achievements.get_unlocked_now() {
+            println!("Achievement unlocked: {} ({})", achievement.get_name(), achievement.get_description());
         }
         Ok(())
     }
     
+    // Adds the finished game to the lifetime stats shown by `snake stats`
+    fn record_stats(&self) -> io::Result<()> {
+        let mut store = StatsStore::load();
+        store.record(self.stats.clone());
+        store.save()
+    }
+    
+    // Games with the same board and mode are compared with each other in the stats
+    fn describe_setup(&self) -> String {
+        let (width, height) = (self.board.get_width(), self.board.get_height());
+        let board = match self.config.get_level_file() {
+            Some(level_file) => level_file.to_string(),
+            None if self.maze.is_some() => format!("{width}x{height} maze"),
+            None => format!("{width}x{height}")
+        };
+        
+        format!("{}, {}", board, self.config.get_mode().get_name())
+    }
+    
+    // What the game ended with, in terms of the mode
+    fn get_result(&self) -> String {
+        match self.config.get_mode() {
+            GameMode::Classic | GameMode::Zen => format!("Score: {}", self.score),
+            GameMode::TimeAttack { duration } => format!("Score in {} seconds: {}", duration, self.score),
+            GameMode::Survival { .. } => format!("Score: {}, survived {} tacts", self.score, self.tick_count),
+            GameMode::Tron => format!("Survived {} tacts", self.tick_count)
+        }
+    }
+    
     pub fn start(&mut self) -> io::Result<()> {
+        let renderer = TerminalRenderer::new(self.get_render_mode(), self.theme.get_frame().clone());
+        self.start_with(Box::new(renderer))
+    }
+    
+    // Plays the game in the terminal, drawing it with the given renderer
+    pub fn start_with(&mut self, renderer: Box<dyn Renderer>) -> io::Result<()> {
+        // Rewinding makes it too easy, and the autopilot's wins aren't the player's
+        if !self.config.is_practice() && self.config.get_controller() == ControllerKind::Keyboard {
+            self.achievements.enable();
+        }
+        if let Some(logger) = Logger::from_env() {
+            logger.log(LogLevel::Info, "game", json!({ "seed": self.seed, "config": &self.config }));
+            self.subscribe(Box::new(logger.clone()));
+            self.logger = Some(logger);
+        }
+        if self.config.get_cues().is_enabled() {
+            let cues = CuePlayer::new(self.config.get_cues());
+            self.subscribe(Box::new(cues));
+        }
+        self.renderer = renderer;
         self.enable_game_mode()?;
         
         self.print_field()?;
@@ -268,9 +1199,54 @@ impl Game {
         
         terminal_handler::clear_screen()?;
         self.print_field()?;
+        self.publish(GameEvent::GameStarted);
         
         self.main_loop()?;
+        if self.death_cause.is_some() {
+            self.show_game_over()?;
+        }
+        self.stats.finish(self.describe_setup(), self.calculate_user_score(), self.tick_count, self.elapsed_time, self.death_cause);
         self.quit()?;
+        self.log(LogLevel::Info, "result", json!({
+            "score": self.calculate_user_score(),
+            "ticks": self.tick_count,
+            "death_cause": self.death_cause,
+            "won": self.has_user_won,
+            "saved": self.has_user_saved
+        }));
+        self.achievements.save()?;
+        // A saved game isn't over yet, it's recorded once it's finished after resuming
+        if !self.has_user_saved {
+            self.record_stats()?;
+        }
+        
+        Ok(())
+    }
+    
+    // Replays the last few tacts slowly, then stays on the fatal one until a key is pressed
+    fn show_game_over(&mut self) -> io::Result<()> {
+        for frame in &self.recent_frames {
+            self.renderer.render(frame)?;
+            Game::sleep(REPLAY_PAUSE_TIME);
+        }
+        
+        let cause = self.death_cause.map(|cause| cause.describe()).unwrap_or_default();
+        let seconds = self.elapsed_time / 1000;
+        let hud = vec![
+            format!("You {}", cause),
+            format!("Score: {}    Length: {}    Survived: {}:{:02} ({} tacts)",
+                self.score, self.snakes[0].get_len(), seconds / 60, seconds % 60, self.tick_count),
+            String::new(),
+            String::from("Press any key to continue")
+        ];
+        let frame = Frame::new(self.build_field(), self.build_looks(), hud, vec![]);
+        self.renderer.render(&frame)?;
+        
+        // Keys still held from the game don't count
+        while terminal_handler::get_key_event()?.is_some() {}
+        while terminal_handler::get_key_event()?.is_none() {
+            Game::sleep(50);
+        }
         
         Ok(())
     }
@@ -278,36 +1254,71 @@ impl Game {
     fn main_loop(&mut self) -> io::Result<()> {
         
         loop {
-            if self.has_user_lost {
+            // In practice, death can still be rewound
+            if self.is_over() && !(self.death_cause.is_some() && self.can_rewind()) {
                 break;
             }
             
+            let render_started_at = Instant::now();
             self.print_field()?;
-            self.generate_apple();
-            Game::sleep(self.config.get_pause_time());
+            self.timing.render_time = render_started_at.elapsed();
+            Game::sleep(self.get_pause_time());
             
+            let tick_count = self.tick_count;
             let user_direction_option = self.get_user_input()?;
             
             if self.has_user_quit {
                 break;
             }
             
-            if let Some(user_direction) = user_direction_option {
-                self.change_snake_direction(user_direction);
+            if self.is_paused {
+                continue;
             }
-            
-            if self.is_snake_head_on_apple() {
-                self.grow_snake();
-                self.hide_apple();
-            } else {
-                self.move_snake_forward();
+            if self.tick_count != tick_count {
+                // Just rewound
+                continue;
+            }
+            if self.is_rewinding || self.death_cause.is_some() {
+                // Waiting for the player to go on from the moment rewound to, or to rewind the death
+                if self.death_cause.is_none() && user_direction_option.is_some() {
+                    self.is_rewinding = false;
+                    self.timed_tick(user_direction_option);
+                }
+                continue;
             }
+            
+            self.timed_tick(user_direction_option);
         }
         
         Ok(())
     }
     
-    fn build_field(&self) -> Vec<Vec<char>> {
+    // A tact played in real time, measured for the debug overlay and the log
+    fn timed_tick(&mut self, pressed_direction: Option<Direction>) {
+        let started_at = Instant::now();
+        self.tick(pressed_direction);
+        let tick_time = started_at.elapsed();
+        
+        if let Some(last_tick_at) = self.timing.last_tick_at {
+            self.timing.tick_interval = started_at - last_tick_at;
+        }
+        self.timing.last_tick_at = Some(started_at);
+        
+        self.log(LogLevel::Trace, "timing", json!({
+            "tick_us": tick_time.as_micros() as u64,
+            "render_us": self.timing.render_time.as_micros() as u64,
+            "interval_ms": self.timing.tick_interval.as_millis() as u64,
+            "pause_time": self.get_pause_time()
+        }));
+    }
+    
+    fn log<T: Serialize>(&self, level: LogLevel, kind: &str, data: T) {
+        if let Some(logger) = &self.logger {
+            logger.log(level, kind, data);
+        }
+    }
+    
+    pub fn build_field(&self) -> Vec<Vec<char>> {
         let mut field = vec![
             vec![' '; self.board.get_width()]; self.board.get_height()
         ];
@@ -322,58 +1333,394 @@ impl Game {
             }
         }
         
-        if let Some(apple_point) = self.apple {
-            field[apple_point.get_y() as usize][apple_point.get_x() as usize] = '$';
+        // Walls about to rise blink for a few tacts beforehand
+        let rising_wall_glyph = if self.tick_count.is_multiple_of(2) { '+' } else { 'x' };
+        for point in self.maze_shifter.get_rising_walls() {
+            field[point.get_y() as usize][point.get_x() as usize] = rising_wall_glyph;
+        }
+        
+        if self.is_hint_shown && let Some(cycle) = &self.hint_cycle {
+            for point in cycle.plan_path(&self.build_view(None), 0) {
+                field[point.get_y() as usize][point.get_x() as usize] = '.';
+            }
+        }
+        
+        // The ghost only shows on free cells, it can't get in the way
+        if let Some(ghost) = &self.ghost && !ghost.is_over() {
+            for seg in ghost.get_snake().get_segments() {
+                if let Some(cell) = field.get_mut(seg.get_y() as usize).and_then(|row| row.get_mut(seg.get_x() as usize))
+                        && matches!(*cell, ' ' | '.') {
+                    *cell = '~';
+                }
+            }
+        }
+        
+        for ((entrance, exit), glyph) in self.portals.iter().zip(&self.portal_glyphs) {
+            field[entrance.get_y() as usize][entrance.get_x() as usize] = *glyph;
+            field[exit.get_y() as usize][exit.get_x() as usize] = *glyph;
+        }
+        
+        for food in &self.foods {
+            let position = food.get_position();
+            field[position.get_y() as usize][position.get_x() as usize] = food.get_kind().get_glyph();
+        }
+        
+        for power_up in &self.power_ups {
+            let position = power_up.get_position();
+            field[position.get_y() as usize][position.get_x() as usize] = power_up.get_kind().get_glyph();
         }
         
-        for seg in self.snake.get_segments() {
-            field[seg.get_y() as usize][seg.get_x() as usize] = '*';
+        for opponent in self.snakes.iter().skip(1) {
+            for seg in opponent.get_segments() {
+                Game::put_on_field(&mut field, seg, 'o');
+            }
+            Game::put_on_field(&mut field, opponent.get_head(), 'O');
         }
         
-        let head = self.snake.get_head();
-        field[head.get_y() as usize][head.get_x() as usize] = '@';
+        let player = &self.snakes[0];
+        for seg in player.get_segments() {
+            Game::put_on_field(&mut field, seg, '*');
+        }
+        Game::put_on_field(&mut field, player.get_head(), '@');
         
         field
     }
     
-    pub fn print_field(&self) -> io::Result<()> {
-        terminal_handler::reset_cursor_position()?;
+    // Generated portals are numbered from 1, pairs past the ninth reuse the digits
+    fn get_portal_glyph(index: usize) -> char {
+        char::from_digit((index % 9) as u32 + 1, 10).expect("Always a single digit")
+    }
+    
+    // A dead snake's head may end up outside the board, there's nothing to draw then
+    fn put_on_field(field: &mut [Vec<char>], point: &Point, cell: char) {
+        if point.get_x() < 0 || point.get_y() < 0 {
+            return;
+        }
         
-        let mut frame = String::new();
+        if let Some(field_cell) = field.get_mut(point.get_y() as usize)
+                .and_then(|row| row.get_mut(point.get_x() as usize)) {
+            *field_cell = cell;
+        }
+    }
+    
+    pub fn print_field(&mut self) -> io::Result<()> {
+        let frame = self.build_frame();
+        self.renderer.render(&frame)?;
+        self.log_frame(&frame);
         
-        for _ in 0..self.board.get_width() + 2 {
-            frame.push('-');
+        // Frames are only kept while the game goes on, not while it waits on a rewind
+        if !self.is_over() && !self.is_rewinding {
+            self.recent_frames.push_back(frame);
+            if self.recent_frames.len() > REPLAY_FRAMES {
+                self.recent_frames.pop_front();
+            }
         }
-        frame.push_str("\r\n");
         
-        let field = self.build_field();
-        for row in field {
-            frame.push('|');
-            
-            for cell in row {
-                frame.push_str(&match cell {
-                    '#' => format!("{}", cell.dark_red()),
-                    '$' => format!("{}", cell.green()),
-                    '@' | '*' => String::from(cell),
-                    _ => String::from(" ")
-                });
+        Ok(())
+    }
+    
+    // What the screen shows right now, whatever draws it
+    pub fn build_frame(&self) -> Frame {
+        let overlays = if self.is_debug_shown { self.build_debug_overlay() } else { vec![] };
+        Frame::new(self.build_field(), self.build_looks(), self.build_hud(), overlays)
+    }
+    
+    // Every frame goes to the log as plain text, which is a lot, so only when tracing
+    fn log_frame(&self, frame: &Frame) {
+        let Some(logger) = &self.logger else {
+            return;
+        };
+        if !logger.is_enabled(LogLevel::Trace) {
+            return;
+        }
+        
+        let mut renderer = PlainRenderer::new(Vec::new());
+        if renderer.render(frame).is_ok() {
+            logger.log(LogLevel::Trace, "frame", String::from_utf8_lossy(&renderer.into_inner()));
+        }
+    }
+    
+    fn build_hud(&self) -> Vec<String> {
+        let mut hud = vec![];
+        
+        if let Some(title) = &self.title {
+            hud.push(title.clone());
+        }
+        if let Some(target_length) = self.config.get_target_length() {
+            hud.push(format!("Length: {}/{}", self.snakes[0].get_len(), target_length));
+        }
+        
+        let speed = 1000.0 / self.get_pause_time().max(1) as f64;
+        hud.push(format!("Your score: {}    Speed: {:.1} tacts/s", self.calculate_user_score(), speed));
+        match self.config.get_mode() {
+            GameMode::TimeAttack { duration } => {
+                let time_left = (duration * 1000).saturating_sub(self.elapsed_time).div_ceil(1000);
+                hud.push(format!("Time left: {}:{:02}", time_left / 60, time_left % 60));
             }
-            
-            frame.push('|');
-            frame.push_str("\r\n");
+            GameMode::Survival { hunger_ticks, .. } => {
+                let ticks_left = (self.last_meal_tick + hunger_ticks).saturating_sub(self.tick_count);
+                hud.push(format!("Hunger: shrinking in {} tacts", ticks_left));
+            }
+            _ => {}
         }
+        if self.snakes.len() > 1 {
+            hud.push(format!("Opponents left: {}", self.snakes.len() - 1));
+        }
+        
+        let effects: Vec<String> = self.get_effects().iter()
+            .map(|(effect, remaining)| format!("{} {}", effect.get_name(), remaining))
+            .collect();
+        let effects = if effects.is_empty() { String::from("none") } else { effects.join(", ") };
+        hud.push(format!("Effects: {}", effects));
         
-        for _ in 0..self.board.get_width() + 2 {
-            frame.push('-');
+        let vision_config = self.config.get_vision();
+        if vision_config.is_enabled() && vision_config.has_compass() {
+            hud.push(format!("Apple: {}", self.get_compass_hint()));
+        }
+        if self.rewind.is_some() {
+            hud.push(format!("Practice: {}", self.get_practice_hint()));
         }
-        frame.push_str("\r\n");
+        if let Some(ghost) = &self.ghost {
+            hud.push(format!("Ghost: {}", self.get_ghost_hint(ghost)));
+        }
+        // Kept even when empty, so the HUD doesn't jump when a toast comes and goes
+        let toast = match self.achievements.get_toast() {
+            _ if self.is_paused => String::from("Paused, press P to go on"),
+            Some(achievement) => format!("Achievement unlocked: {}!", achievement.get_name()),
+            None => String::new()
+        };
+        hud.push(toast);
         
-        print!("{}", frame);
+        hud
+    }
+    
+    fn build_debug_overlay(&self) -> Vec<String> {
+        let player = &self.snakes[0];
+        let head = player.get_head();
+        let tick_rate = match self.timing.tick_interval.as_secs_f64() {
+            0.0 => 0.0,
+            interval => 1.0 / interval
+        };
+        let vertical_debt = match player.get_vertical_debt() {
+            None => "off",
+            Some(true) => "skips next vertical step",
+            Some(false) => "moves on next vertical step"
+        };
         
-        print!("\r\nYour score: {}\r\n\r\n", self.calculate_user_score());
+        vec![
+            format!("Debug: {:.1} tacts/s, render {:.2} ms, seed {}",
+                tick_rate, self.timing.render_time.as_secs_f64() * 1000.0, self.seed),
+            format!("Head: ({}, {}) {:?}, free cells: {}, vertical debt: {}",
+                head.get_x(), head.get_y(), player.get_direction(), self.count_free_cells(), vertical_debt)
+        ]
+    }
+    
+    // Cells with neither a wall nor a snake on them
+    fn count_free_cells(&self) -> usize {
+        let mut is_taken = vec![vec![false; self.board.get_width()]; self.board.get_height()];
+        if let Some(maze) = &self.maze {
+            is_taken = maze.clone();
+        }
+        for segment in self.snakes.iter().flat_map(|snake| snake.get_segments()) {
+            if let Some(cell) = is_taken.get_mut(segment.get_y() as usize)
+                    .and_then(|row| row.get_mut(segment.get_x() as usize)) {
+                *cell = true;
+            }
+        }
         
-        stdout().flush()?;
+        is_taken.iter().flatten().filter(|is_taken| !**is_taken).count()
+    }
+    
+    fn get_ghost_hint(&self, ghost: &Ghost) -> String {
+        let crashed = if ghost.is_over() { "crashed, " } else { "" };
+        let score = self.calculate_user_score();
+        let ghost_score = ghost.get_score();
         
-        Ok(())
+        if score > ghost_score {
+            format!("{crashed}you're ahead by {}", score - ghost_score)
+        } else if score < ghost_score {
+            format!("{crashed}you're behind by {}", ghost_score - score)
+        } else {
+            format!("{crashed}you're even")
+        }
+    }
+    
+    fn get_practice_hint(&self) -> String {
+        if let Some(cause) = self.death_cause {
+            format!("you {}, hold R to rewind or Q to give up", cause.describe())
+        } else if self.is_rewinding {
+            String::from("press a direction to go on from here")
+        } else {
+            format!("hold R to rewind, each rewind costs {} score", self.config.get_rewind().get_penalty())
+        }
+    }
+    
+    // Points toward the nearest edible food while none is in sight
+    fn get_compass_hint(&self) -> String {
+        let head = self.snakes[0].get_head();
+        let edible_foods = Game::get_edible_positions(&self.foods);
+        
+        if edible_foods.iter().any(|food| self.vision.get_visibility(food) == Visibility::Visible) {
+            return String::from("in sight");
+        }
+        
+        match edible_foods.iter().min_by_key(|food| head.distance_to(food)) {
+            Some(food) => String::from(vision::get_compass_arrow(head, food)),
+            None => String::from("none")
+        }
+    }
+    
+    // Half blocks show nothing but colors, so without them the cells are drawn as glyphs
+    fn get_render_mode(&self) -> RenderMode {
+        match self.config.get_render_mode() {
+            RenderMode::HalfBlock if !self.theme.has_colors() => RenderMode::DoubleWidth,
+            render_mode => render_mode
+        }
+    }
+    
+    // How every cell of the field looks in the theme, with what's out of sight hidden
+    fn build_looks(&self) -> Vec<Vec<Look>> {
+        let player = &self.snakes[0];
+        let field = self.build_field();
+        
+        // Going from the tail, so the head wins where the body overlaps it
+        let mut segment_indices = vec![vec![None; self.board.get_width()]; self.board.get_height()];
+        for (index, segment) in player.get_segments().iter().enumerate().rev() {
+            if let Some(cell) = segment_indices.get_mut(segment.get_y() as usize)
+                    .and_then(|row| row.get_mut(segment.get_x() as usize)) {
+                *cell = Some(index);
+            }
+        }
+        
+        field.iter()
+            .enumerate()
+            .map(|(y, row)| row.iter()
+                .enumerate()
+                .map(|(x, cell)| {
+                    let point = Point::new(x as i32, y as i32);
+                    
+                    // The player always knows where their own body is
+                    if self.death_point == Some(point) {
+                        return self.theme.get_fatal_look();
+                    }
+                    
+                    if let Some(index) = segment_indices[y][x] && matches!(cell, '@' | '*') {
+                        return self.theme.get_player_look(index, player.get_len());
+                    }
+                    
+                    match self.vision.get_visibility(&point) {
+                        Visibility::Hidden => self.theme.get_fog_look(),
+                        Visibility::Remembered => self.theme.get_wall_look(&self.get_wall_neighbors(&point), true),
+                        Visibility::Visible if *cell == '#' => {
+                            self.theme.get_wall_look(&self.get_wall_neighbors(&point), false)
+                        }
+                        Visibility::Visible => self.theme.get_look(*cell)
+                    }
+                })
+                .collect())
+            .collect()
+    }
+    
+    fn get_wall_neighbors(&self, point: &Point) -> Vec<Direction> {
+        let Some(maze) = &self.maze else {
+            return vec![];
+        };
+        
+        DIRECTIONS.into_iter()
+            .filter(|direction| {
+                let neighbor = point.moved(*direction);
+                neighbor.get_x() >= 0 && neighbor.get_y() >= 0 &&
+                    maze.get(neighbor.get_y() as usize)
+                        .and_then(|row| row.get(neighbor.get_x() as usize))
+                        .is_some_and(|is_wall| *is_wall)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    // Rows 0 to 7 of an 8x8 board, going right on even rows and left on odd ones, from (0, 0)
+    fn snake_cells() -> Vec<Point> {
+        (0..8)
+            .flat_map(|y| (0..8).map(move |x| if y % 2 == 0 { Point::new(x, y) } else { Point::new(7 - x, y) }))
+            .collect()
+    }
+    
+    fn open_game() -> Game {
+        Game::with_seed(Config::new(8, 8, 100, false, 0.0), 7)
+    }
+    
+    fn draw(game: &Game) -> String {
+        let mut renderer = PlainRenderer::new(Vec::new());
+        renderer.render(&game.build_frame()).unwrap();
+        String::from_utf8(renderer.into_inner()).unwrap()
+    }
+    
+    fn temp_filename(name: &str) -> String {
+        std::env::temp_dir().join(format!("snake-{}-{name}", std::process::id())).to_string_lossy().into_owned()
+    }
+    
+    #[test]
+    fn board_covered_by_player_is_cleared() {
+        let mut game = open_game();
+        let mut cells = snake_cells();
+        cells.reverse();
+        game.snakes = vec![Snake::from_segments(cells)];
+        game.foods.clear();
+        
+        game.generate_foods();
+        
+        assert!(game.has_user_won);
+        assert!(game.foods.is_empty());
+    }
+    
+    #[test]
+    fn board_filled_by_opponent_is_not_cleared() {
+        let mut game = open_game();
+        let mut cells = snake_cells();
+        cells.reverse();
+        let opponent = cells.split_off(2);
+        game.snakes = vec![Snake::from_segments(cells), Snake::from_segments(opponent)];
+        game.foods.clear();
+        
+        game.generate_foods();
+        
+        assert!(!game.has_user_won);
+        assert!(game.foods.is_empty());
+    }
+    
+    #[test]
+    fn resumed_game_goes_on_the_same_way() {
+        let filename = temp_filename("save.json");
+        let mut game = Game::with_seed(Config::new(16, 8, 100, true, 0.8), 3);
+        for direction in [None, Some(Direction::UP), None, Some(Direction::LEFT)] {
+            game.tick(direction);
+        }
+        
+        storage::save(&filename, &game).unwrap();
+        let mut resumed = Game::resume_from(&filename).unwrap().unwrap();
+        
+        assert!(!std::path::Path::new(&filename).exists());
+        assert_eq!(draw(&resumed), draw(&game));
+        for direction in [Some(Direction::DOWN), None, Some(Direction::RIGHT), None, None, None] {
+            game.tick(direction);
+            resumed.tick(direction);
+            assert_eq!(draw(&resumed), draw(&game));
+        }
+        assert!(!game.is_over());
+    }
+    
+    #[test]
+    fn broken_save_is_not_resumed() {
+        let filename = temp_filename("broken.json");
+        std::fs::write(&filename, "{ \"score\": ").unwrap();
+        
+        assert!(Game::resume_from(&filename).unwrap().is_none());
+        let corrupt_filename = format!("{filename}.corrupt");
+        assert!(std::path::Path::new(&corrupt_filename).exists());
+        std::fs::remove_file(corrupt_filename).unwrap();
     }
 }
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_DURATION: u64 = 120;
+const DEFAULT_HUNGER_TICKS: u64 = 40;
+const DEFAULT_FOOD_CHANCE: f64 = 0.05;
+
+// Changes what scores and what ends the game
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GameMode {
+    #[default]
+    Classic,
+    // Score as much as possible before `duration` seconds of game time run out
+    TimeAttack {
+        #[serde(default = "default_duration")]
+        duration: u64
+    },
+    // Food only shows up with `food_chance` per tact, and the snake loses
+    // a segment every `hunger_ticks` tacts without eating
+    Survival {
+        #[serde(default = "default_hunger_ticks")]
+        hunger_ticks: u64,
+        #[serde(default = "default_food_chance")]
+        food_chance: f64
+    },
+    // Nothing kills the snake, it just stops in front of obstacles
+    Zen,
+    // There's no food, snakes leave a trail that never shrinks and score is tacts survived
+    Tron
+}
+
+impl GameMode {
+    pub fn get_name(&self) -> &'static str {
+        match *self {
+            GameMode::Classic => "classic",
+            GameMode::TimeAttack { .. } => "time attack",
+            GameMode::Survival { .. } => "survival",
+            GameMode::Zen => "zen",
+            GameMode::Tron => "tron"
+        }
+    }
+}
+
+fn default_duration() -> u64 {
+    DEFAULT_DURATION
+}
+
+fn default_hunger_ticks() -> u64 {
+    DEFAULT_HUNGER_TICKS
+}
+
+fn default_food_chance() -> f64 {
+    DEFAULT_FOOD_CHANCE
+}
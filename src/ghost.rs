@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    direction::Direction,
+    game::Game,
+    snake::Snake,
+    storage
+};
+
+const FILENAME: &str = "ghosts.json";
+
+// The directions the player pressed during a run, with the tacts they were pressed at.
+// Games are deterministic for a seed, so that's all it takes to play the run again
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Recording {
+    inputs: Vec<(u64, Direction)>
+}
+
+impl Recording {
+    pub fn record(&mut self, tick_count: u64, direction: Direction) {
+        self.inputs.push((tick_count, direction));
+    }
+
+    pub fn get_input(&self, tick_count: u64) -> Option<Direction> {
+        self.inputs.binary_search_by_key(&tick_count, |(tick, _)| *tick)
+            .ok()
+            .map(|index| self.inputs[index].1)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BestRun {
+    score: u32,
+    recording: Recording
+}
+
+impl BestRun {
+    pub fn get_recording(&self) -> &Recording {
+        &self.recording
+    }
+}
+
+// The best run played on every seed so far
+#[derive(Serialize, Deserialize, Default)]
+pub struct BestRuns {
+    runs: HashMap<u64, BestRun>
+}
+
+impl BestRuns {
+    pub fn load() -> BestRuns {
+        storage::load(FILENAME)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        storage::save(FILENAME, self)
+    }
+
+    pub fn find(&self, seed: u64) -> Option<&BestRun> {
+        self.runs.get(&seed)
+    }
+
+    // Keeps the run only if it beats the best one on the seed; returns whether it did
+    pub fn record(&mut self, seed: u64, score: u32, recording: Recording) -> bool {
+        if self.find(seed).is_some_and(|best| best.score >= score) {
+            return false;
+        }
+
+        self.runs.insert(seed, BestRun { score, recording });
+        true
+    }
+}
+
+// A recorded run played again, tact by tact, alongside the player's game
+pub struct Ghost {
+    game: Box<Game>,
+    recording: Recording
+}
+
+impl Ghost {
+    // `game` has to be built with the same config and seed as the recorded one
+    pub fn new(game: Game, recording: Recording) -> Ghost {
+        Ghost { game: Box::new(game), recording }
+    }
+
+    pub fn tick(&mut self) {
+        if !self.game.is_over() {
+            let input = self.recording.get_input(self.game.get_tick_count());
+            self.game.tick(input);
+        }
+    }
+
+    pub fn get_snake(&self) -> &Snake {
+        self.game.get_player()
+    }
+
+    pub fn get_score(&self) -> u32 {
+        self.game.calculate_user_score()
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.game.is_over()
+    }
+}
@@ -0,0 +1,345 @@
+use crate::{
+    ai::{self, SurvivalController},
+    board::Board,
+    controller::{Controller, GameView},
+    direction::{Direction, DIRECTIONS},
+    point::Point
+};
+
+// Shortcuts get disabled once the snake covers this share of the free cells,
+// from then on it just follows the cycle
+const SHORTCUT_FILL_LIMIT: f64 = 0.5;
+// Cells kept between the head and the tail when shortcutting, so growing can't close the gap
+const SHORTCUT_TAIL_MARGIN: usize = 3;
+
+// A closed path visiting every free cell of the board exactly once
+pub struct HamiltonianCycle {
+    cells: Vec<Point>,
+    order: Vec<Vec<Option<usize>>>
+}
+
+impl HamiltonianCycle {
+    pub fn build(board: &Board, maze: Option<&[Vec<bool>]>) -> Option<HamiltonianCycle> {
+        let path = match maze {
+            None if board.get_height().is_multiple_of(2) => build_row_zigzag(board.get_width(), board.get_height()),
+            None if board.get_width().is_multiple_of(2) => build_row_zigzag(board.get_height(), board.get_width())
+                .into_iter()
+                .map(|point| Point::new(point.get_y(), point.get_x()))
+                .collect(),
+            None => return None,
+            Some(maze) => build_block_tour(board, maze)?
+        };
+
+        let mut order = vec![vec![None; board.get_width()]; board.get_height()];
+        for (index, point) in path.iter().enumerate() {
+            order[point.get_y() as usize][point.get_x() as usize] = Some(index);
+        }
+
+        Some(HamiltonianCycle { cells: path, order })
+    }
+
+    fn get_index(&self, point: &Point) -> Option<usize> {
+        if point.get_x() < 0 || point.get_y() < 0 {
+            return None;
+        }
+        self.order.get(point.get_y() as usize)?.get(point.get_x() as usize).cloned().flatten()
+    }
+
+    // How many steps along the cycle it takes to get from `from` to `to`
+    fn distance(&self, from: &Point, to: &Point) -> Option<usize> {
+        let from_index = self.get_index(from)?;
+        let to_index = self.get_index(to)?;
+        Some((to_index + self.cells.len() - from_index) % self.cells.len())
+    }
+
+    // Picks the next cell for a snake following the cycle. Jumps ahead along the cycle
+    // toward the apple only while the whole body is guaranteed to stay behind the head
+    pub fn choose_next(&self, view: &GameView, head: &Point, tail: &Point, snake_len: usize,
+            apple: Option<&Point>) -> Option<Point> {
+        let tail_distance = self.distance(head, tail)?;
+        let successor = self.cells[(self.get_index(head)? + 1) % self.cells.len()];
+
+        let can_shortcut = (snake_len as f64) < self.cells.len() as f64 * SHORTCUT_FILL_LIMIT;
+        let apple_distance = apple.and_then(|apple| self.distance(head, apple));
+
+        let mut best = if view.is_blocked(&successor) { None } else { Some((1, successor)) };
+
+        if can_shortcut && let Some(apple_distance) = apple_distance {
+            for direction in DIRECTIONS {
+                let next = head.moved(direction);
+                if view.is_blocked(&next) {
+                    continue;
+                }
+
+                let Some(next_distance) = self.distance(head, &next) else {
+                    continue;
+                };
+                let is_before_tail = next_distance + SHORTCUT_TAIL_MARGIN < tail_distance;
+                let is_before_apple = next_distance <= apple_distance;
+                let is_further = best.is_none_or(|(best_distance, _)| next_distance > best_distance);
+
+                if is_before_tail && is_before_apple && is_further {
+                    best = Some((next_distance, next));
+                }
+            }
+        }
+
+        best.map(|(_, point)| point)
+    }
+
+    // The cells the autopilot would walk through to reach the apple, if nothing moved meanwhile
+    pub fn plan_path(&self, view: &GameView, snake_index: usize) -> Vec<Point> {
+        let snake = &view.get_snakes()[snake_index];
+        let tail = *snake.get_segments().back().expect("Snake has no body!");
+        let Some(apple) = view.get_apples().first() else {
+            return vec![];
+        };
+
+        let mut path = vec![];
+        let mut current = *snake.get_head();
+
+        while current != *apple && path.len() < self.cells.len() {
+            match self.choose_next(view, &current, &tail, snake.get_len(), Some(apple)) {
+                Some(next) if !path.contains(&next) => {
+                    path.push(next);
+                    current = next;
+                }
+                _ => break
+            }
+        }
+
+        path
+    }
+}
+
+// Snake-like walk over rows: right along the first row, then back and forth over
+// the other rows leaving out the first column, which leads back to the start.
+// Needs an even number of rows
+fn build_row_zigzag(width: usize, height: usize) -> Vec<Point> {
+    let mut path = vec![];
+
+    for x in 0..width {
+        path.push(Point::new(x as i32, 0));
+    }
+
+    for y in 1..height {
+        let columns: Vec<usize> = if y % 2 == 1 {
+            (1..width).rev().collect()
+        } else {
+            (1..width).collect()
+        };
+
+        for x in columns {
+            path.push(Point::new(x as i32, y as i32));
+        }
+    }
+
+    for y in (1..height).rev() {
+        path.push(Point::new(0, y as i32));
+    }
+
+    path
+}
+
+// Splits the board into 2x2 blocks, connects the free ones with a spanning tree
+// and walks around that tree. Only works if every free cell belongs to a free block
+fn build_block_tour(board: &Board, maze: &[Vec<bool>]) -> Option<Vec<Point>> {
+    if !board.get_width().is_multiple_of(2) || !board.get_height().is_multiple_of(2) {
+        return None;
+    }
+
+    let (blocks_width, blocks_height) = (board.get_width() / 2, board.get_height() / 2);
+    let is_block_free = |bx: usize, by: usize| {
+        (0..2).all(|dy| (0..2).all(|dx| !maze[by * 2 + dy][bx * 2 + dx]))
+    };
+
+    let mut free_blocks = vec![vec![false; blocks_width]; blocks_height];
+    let mut free_cells_count = 0;
+    for (by, row) in free_blocks.iter_mut().enumerate() {
+        for (bx, block) in row.iter_mut().enumerate() {
+            *block = is_block_free(bx, by);
+            let free_cells = (0..2)
+                .flat_map(|dy| (0..2).map(move |dx| (dx, dy)))
+                .filter(|(dx, dy)| !maze[by * 2 + dy][bx * 2 + dx])
+                .count();
+
+            if !*block && free_cells > 0 {
+                return None;
+            }
+            free_cells_count += free_cells;
+        }
+    }
+
+    let root = (0..blocks_height)
+        .flat_map(|by| (0..blocks_width).map(move |bx| (bx, by)))
+        .find(|(bx, by)| free_blocks[*by][*bx])?;
+
+    // Tree edges leading right and down from every block
+    let mut right_edges = vec![vec![false; blocks_width]; blocks_height];
+    let mut down_edges = vec![vec![false; blocks_width]; blocks_height];
+    let mut visited = vec![vec![false; blocks_width]; blocks_height];
+    let mut stack = vec![root];
+    visited[root.1][root.0] = true;
+
+    while let Some((bx, by)) = stack.pop() {
+        let neighbors = [
+            (bx.wrapping_sub(1), by),
+            (bx + 1, by),
+            (bx, by.wrapping_sub(1)),
+            (bx, by + 1)
+        ];
+
+        for (nx, ny) in neighbors {
+            if nx >= blocks_width || ny >= blocks_height || !free_blocks[ny][nx] || visited[ny][nx] {
+                continue;
+            }
+            visited[ny][nx] = true;
+
+            match (nx.cmp(&bx), ny.cmp(&by)) {
+                (std::cmp::Ordering::Greater, _) => right_edges[by][bx] = true,
+                (std::cmp::Ordering::Less, _) => right_edges[ny][nx] = true,
+                (_, std::cmp::Ordering::Greater) => down_edges[by][bx] = true,
+                _ => down_edges[ny][nx] = true
+            }
+            stack.push((nx, ny));
+        }
+    }
+
+    let successor = |point: &Point| {
+        let (x, y) = (point.get_x() as usize, point.get_y() as usize);
+        let (bx, by) = (x / 2, y / 2);
+
+        // Inside a block the walk goes clockwise, tree edges redirect it into the neighbors
+        let direction = match (x % 2, y % 2) {
+            (0, 0) if by > 0 && down_edges[by - 1][bx] => Direction::UP,
+            (0, 0) => Direction::RIGHT,
+            (1, 0) if right_edges[by][bx] => Direction::RIGHT,
+            (1, 0) => Direction::DOWN,
+            (1, _) if down_edges[by][bx] => Direction::DOWN,
+            (1, _) => Direction::LEFT,
+            _ if bx > 0 && right_edges[by][bx - 1] => Direction::LEFT,
+            _ => Direction::UP
+        };
+        point.moved(direction)
+    };
+
+    let start = Point::new(root.0 as i32 * 2, root.1 as i32 * 2);
+    let mut path = vec![start];
+    let mut current = successor(&start);
+
+    while current != start && path.len() < free_cells_count {
+        path.push(current);
+        current = successor(&current);
+    }
+
+    if current != start || path.len() != free_cells_count {
+        return None;
+    }
+
+    Some(path)
+}
+
+// Perfect-play autopilot following a Hamiltonian cycle, with shortcuts toward the apple.
+// Falls back to the survival bot when the board has no cycle it can build
+pub struct HamiltonianController {
+    cycle: Option<Option<HamiltonianCycle>>,
+    // The version of the maze the cycle was built for, as mazes may shift during play
+    cycle_maze_version: u64,
+    fallback: SurvivalController
+}
+
+impl HamiltonianController {
+    pub fn new() -> HamiltonianController {
+        HamiltonianController { cycle: None, cycle_maze_version: 0, fallback: SurvivalController }
+    }
+}
+
+impl Controller for HamiltonianController {
+    fn next_direction(&mut self, view: &GameView, snake_index: usize) -> Option<Direction> {
+        if self.cycle_maze_version != view.get_maze_version() {
+            self.cycle = None;
+            self.cycle_maze_version = view.get_maze_version();
+        }
+
+        // Portals would throw the snake off the cycle
+        let cycle = self.cycle.get_or_insert_with(|| {
+            if view.get_portals().is_empty() {
+                HamiltonianCycle::build(view.get_board(), view.get_maze())
+            } else {
+                None
+            }
+        });
+
+        let snake = &view.get_snakes()[snake_index];
+        let head = snake.get_head();
+        let tail = snake.get_segments().back().expect("Snake has no body!");
+
+        let next = cycle.as_ref().and_then(|cycle| {
+            cycle.choose_next(view, head, tail, snake.get_len(), view.get_apples().first())
+        });
+
+        match next {
+            Some(next) => ai::direction_towards(view, head, &next),
+            None => self.fallback.next_direction(view, snake_index)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_closed_cycle(cycle: &HamiltonianCycle, maze: Option<&[Vec<bool>]>, free_cells_count: usize) {
+        assert_eq!(cycle.cells.len(), free_cells_count);
+        for (index, cell) in cycle.cells.iter().enumerate() {
+            assert_eq!(cycle.get_index(cell), Some(index), "{cell:?} is visited more than once");
+            assert!(maze.is_none_or(|maze| !maze[cell.get_y() as usize][cell.get_x() as usize]), "{cell:?} is a wall");
+
+            let next = cycle.cells[(index + 1) % cycle.cells.len()];
+            assert_eq!(cell.distance_to(&next), 1, "{cell:?} isn't next to {next:?}");
+        }
+    }
+
+    #[test]
+    fn open_boards_are_covered_by_a_closed_cycle() {
+        for (width, height) in [(8, 8), (9, 8), (8, 9), (16, 10)] {
+            let board = Board::new(width, height).unwrap();
+            let cycle = HamiltonianCycle::build(&board, None).unwrap();
+
+            assert_closed_cycle(&cycle, None, (width * height) as usize);
+        }
+    }
+
+    #[test]
+    fn odd_open_boards_have_no_cycle() {
+        let board = Board::new(9, 9).unwrap();
+
+        assert!(HamiltonianCycle::build(&board, None).is_none());
+    }
+
+    #[test]
+    fn mazes_of_free_blocks_are_covered_by_a_closed_cycle() {
+        let board = Board::new(12, 8).unwrap();
+        let mut maze = vec![vec![false; 12]; 8];
+        // Walls filling whole 2x2 blocks, leaving a ring with two dead ends
+        for (bx, by) in [(1, 1), (2, 1), (3, 1), (4, 1), (1, 2), (3, 2)] {
+            for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                maze[by * 2 + dy][bx * 2 + dx] = true;
+            }
+        }
+        let free_cells_count = maze.iter().flatten().filter(|is_wall| !**is_wall).count();
+
+        let cycle = HamiltonianCycle::build(&board, Some(&maze)).unwrap();
+
+        assert_closed_cycle(&cycle, Some(&maze), free_cells_count);
+    }
+
+    #[test]
+    fn mazes_splitting_blocks_have_no_cycle() {
+        let board = Board::new(8, 8).unwrap();
+        let mut maze = vec![vec![false; 8]; 8];
+        maze[3][3] = true;
+
+        assert!(HamiltonianCycle::build(&board, Some(&maze)).is_none());
+    }
+}
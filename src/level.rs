@@ -0,0 +1,94 @@
+use std::fs;
+
+use crate::{
+    maze::Portal,
+    point::Point
+};
+
+// A hand-authored board: `#` is a wall, any other character is free,
+// and the same digit written twice marks both ends of a portal
+pub struct Level {
+    width: u32,
+    height: u32,
+    maze: Vec<Vec<bool>>,
+    portals: Vec<Portal>,
+    // The digit each portal was written with, shown on the board the same way
+    portal_glyphs: Vec<char>
+}
+
+impl Level {
+    pub fn read(filename: &str) -> Level {
+        Level::try_read(filename).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_read(filename: &str) -> Result<Level, String> {
+        let level_data = fs::read_to_string(filename)
+            .map_err(|_| format!("Failed to read level {filename}. Check it again"))?;
+
+        Level::parse(&level_data)
+            .map_err(|error| format!("Failed to parse level {filename}: {error}"))
+    }
+
+    pub fn parse(level_data: &str) -> Result<Level, String> {
+        let mut rows: Vec<Vec<char>> = level_data.lines()
+            .map(|line| line.trim_end().chars().collect())
+            .collect();
+        while rows.last().is_some_and(|row| row.is_empty()) {
+            rows.pop();
+        }
+
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        let height = rows.len();
+
+        let mut maze = vec![vec![false; width]; height];
+        let mut portal_ends: Vec<Vec<Point>> = vec![vec![]; 10];
+
+        for (y, row) in rows.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                match cell {
+                    '#' => maze[y][x] = true,
+                    '0'..='9' => {
+                        let digit = cell.to_digit(10).expect("Matched a digit") as usize;
+                        portal_ends[digit].push(Point::new(x as i32, y as i32));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut portals = vec![];
+        let mut portal_glyphs = vec![];
+        for (digit, ends) in portal_ends.iter().enumerate() {
+            match ends.as_slice() {
+                [] => {}
+                [entrance, exit] => {
+                    portals.push((*entrance, *exit));
+                    portal_glyphs.push(char::from_digit(digit as u32, 10).expect("Always a single digit"));
+                }
+                _ => return Err(format!("portal {digit} must have exactly two ends, found {}", ends.len()))
+            }
+        }
+
+        Ok(Level { width: width as u32, height: height as u32, maze, portals, portal_glyphs })
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn get_maze(&self) -> &[Vec<bool>] {
+        &self.maze
+    }
+
+    pub fn get_portals(&self) -> &[Portal] {
+        &self.portals
+    }
+
+    pub fn get_portal_glyphs(&self) -> &[char] {
+        &self.portal_glyphs
+    }
+}
@@ -0,0 +1,98 @@
+use std::cell::RefCell;
+use std::env;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::rc::Rc;
+use std::time::Instant;
+
+use serde::Serialize;
+use serde_json::json;
+
+use crate::events::{GameEvent, Observer};
+
+// `SNAKE_LOG=debug` turns logging on, `SNAKE_LOG_FILE` says where to, `snake.log` otherwise
+const LEVEL_VARIABLE: &str = "SNAKE_LOG";
+const FILE_VARIABLE: &str = "SNAKE_LOG_FILE";
+const DEFAULT_FILENAME: &str = "snake.log";
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    // The setup of the game: config, seed, how it ended
+    Info,
+    // Inputs and events
+    Debug,
+    // Timing of every tact
+    Trace
+}
+
+impl LogLevel {
+    fn parse(name: &str) -> Option<LogLevel> {
+        match name.to_lowercase().as_str() {
+            "info" => Some(LogLevel::Info),
+            "debug" => Some(LogLevel::Debug),
+            "trace" => Some(LogLevel::Trace),
+            _ => None
+        }
+    }
+}
+
+struct LogFile {
+    file: File,
+    level: LogLevel,
+    started_at: Instant,
+    // Taken from the events, so every record tells which tact it belongs to
+    tick_count: u64
+}
+
+// Writes one JSON object per line. Clones share the file, so the same log can follow
+// the game's events and be written to directly
+#[derive(Clone)]
+pub struct Logger(Rc<RefCell<LogFile>>);
+
+impl Logger {
+    // None unless the level variable names a known level, or when the log file can't be opened
+    pub fn from_env() -> Option<Logger> {
+        let level = env::var(LEVEL_VARIABLE).ok().and_then(|name| LogLevel::parse(&name))?;
+        let filename = env::var(FILE_VARIABLE).unwrap_or_else(|_| DEFAULT_FILENAME.to_string());
+        let file = match OpenOptions::new().create(true).append(true).open(&filename) {
+            Ok(file) => file,
+            Err(error) => {
+                eprintln!("Failed to open the log file {filename} ({error}), playing without a log. Check {FILE_VARIABLE}");
+                return None;
+            }
+        };
+
+        Some(Logger(Rc::new(RefCell::new(LogFile { file, level, started_at: Instant::now(), tick_count: 0 }))))
+    }
+
+    pub fn is_enabled(&self, level: LogLevel) -> bool {
+        level <= self.0.borrow().level
+    }
+
+    // Logging must never stop the game, so failed writes are dropped
+    pub fn log<T: Serialize>(&self, level: LogLevel, kind: &str, data: T) {
+        if !self.is_enabled(level) {
+            return;
+        }
+
+        let mut log_file = self.0.borrow_mut();
+        let record = json!({
+            "time_ms": log_file.started_at.elapsed().as_millis() as u64,
+            "tick": log_file.tick_count,
+            "level": level,
+            "kind": kind,
+            "data": data
+        });
+        let _ = writeln!(log_file.file, "{record}");
+    }
+}
+
+impl Observer for Logger {
+    fn on_event(&mut self, event: &GameEvent) {
+        if let GameEvent::Tick { tick_count, .. } = *event {
+            self.0.borrow_mut().tick_count = tick_count;
+        }
+        self.log(LogLevel::Debug, "event", event);
+    }
+}
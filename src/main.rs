@@ -6,12 +6,90 @@ mod game;
 mod terminal_handler;
 mod config;
 mod maze;
+mod controller;
+mod ai;
+mod hamiltonian;
+mod death_cause;
+mod env_mode;
+mod difficulty;
+mod food;
+mod power_up;
+mod level;
+mod maze_shifter;
+mod vision;
+mod storage;
+mod campaign;
+mod scores;
+mod daily;
+mod game_mode;
+mod render_mode;
+mod theme;
+mod palette;
+mod rewind;
+mod ghost;
+mod stats;
+mod achievements;
+mod events;
+mod logger;
+mod cues;
+mod renderer;
 
+use config::Config;
+use controller::ControllerKind;
 use game::Game;
-use std::io;
+use std::{env, io};
 
 fn main() -> io::Result<()> {
-    let mut game = Game::new(32, 16);
+    let mode = env::args().nth(1);
+    
+    // `snake env` talks to training agents over stdin/stdout instead of the terminal
+    if mode.as_deref() == Some("env") {
+        return env_mode::run();
+    }
+    
+    // `snake daily` is the same challenge for everyone, so only the config's looks and cues apply
+    if mode.as_deref() == Some("daily") {
+        return daily::run(Config::try_read().ok());
+    }
+    
+    // `snake --resume` goes on with the game saved with X
+    if mode.as_deref() == Some("--resume") {
+        match Game::resume()? {
+            Some(mut game) => game.start()?,
+            None => println!("There's no saved game to resume")
+        }
+        return Ok(());
+    }
+    
+    // `snake stats` shows what all the games played so far add up to
+    if mode.as_deref() == Some("stats") {
+        return stats::run();
+    }
+    
+    // `snake achievements` lists what's been unlocked so far
+    if mode.as_deref() == Some("achievements") {
+        return achievements::run();
+    }
+    
+    let mut config = Config::read();
+    
+    // `snake campaign [level]` plays the levels one after another
+    if mode.as_deref() == Some("campaign") {
+        let level = env::args().nth(2).and_then(|level| level.parse().ok());
+        return campaign::run(config, level);
+    }
+    
+    // `snake practice` allows rewinding with R, at a score penalty
+    if mode.as_deref() == Some("practice") {
+        config = config.with_practice();
+    }
+    
+    // `snake demo` lets the autopilot play on its own, on a board it can clear
+    if mode.as_deref() == Some("demo") {
+        config = config.with_controller(ControllerKind::Hamiltonian).with_open_board();
+    }
+    
+    let mut game = Game::new(config);
     game.start()?;
     
     Ok(())
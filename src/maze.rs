@@ -1,20 +1,23 @@
+use std::collections::VecDeque;
+
 use rand::{Rng, seq::SliceRandom};
 use crate::{
     board::{Board}, point::{Point, generate_point}
 };
 
-pub fn build_maze(board: &Board, remove_chance: f64) -> Vec<Vec<bool>> {
+// A pair of cells connected to each other: entering one takes the snake out of the other
+pub type Portal = (Point, Point);
+
+pub fn build_maze(board: &Board, remove_chance: f64, rng: &mut impl Rng) -> Vec<Vec<bool>> {
     let mut maze = vec![vec![true; board.get_width()]; board.get_height()];
     
-    perform_dfs(&mut maze, board);
-    erode_dfs_maze(&mut maze, remove_chance);
+    perform_dfs(&mut maze, board, rng);
+    erode_dfs_maze(&mut maze, remove_chance, rng);
 
     maze
 }
 
-fn erode_dfs_maze(maze: &mut Vec<Vec<bool>>, remove_chance: f64) {
-    let mut rng = rand::rng();
-    
+fn erode_dfs_maze(maze: &mut [Vec<bool>], remove_chance: f64, rng: &mut impl Rng) {
     for row in maze.iter_mut() {
         for cell in row.iter_mut() {
             if *cell && rng.random_bool(remove_chance) {
@@ -24,8 +27,8 @@ fn erode_dfs_maze(maze: &mut Vec<Vec<bool>>, remove_chance: f64) {
     }
 }
 
-fn perform_dfs(maze: &mut Vec<Vec<bool>>, board: &Board) {
-    let entry_point = generate_point(board.get_width() as u32, board.get_height() as u32);
+fn perform_dfs(maze: &mut [Vec<bool>], board: &Board, rng: &mut impl Rng) {
+    let entry_point = generate_point(board.get_width() as u32, board.get_height() as u32, rng);
 
     let mut stack = vec![entry_point];
     maze[entry_point.get_y() as usize][entry_point.get_x() as usize] = false;
@@ -36,10 +39,10 @@ fn perform_dfs(maze: &mut Vec<Vec<bool>>, board: &Board) {
         
         let mut is_leaf = true;
 
-        for (new_x, new_y) in generate_random_neighbors(curr_x, curr_y) {
+        for (new_x, new_y) in generate_random_neighbors(curr_x, curr_y, rng) {
             if new_x < board.get_width() && new_y < board.get_height() &&
                     maze[new_y][new_x] &&
-                    !is_node_next_to_visited(new_x, new_y, curr_x, curr_y, &maze, board) {
+                    !is_node_next_to_visited(new_x, new_y, curr_x, curr_y, maze, board) {
                 stack.push(Point::new(new_x as i32, new_y as i32));
                 is_leaf = false;
                 break;
@@ -61,17 +64,16 @@ fn get_neighbors(x: usize, y: usize) -> [(usize, usize); 4] {
     ]
 }
 
-fn generate_random_neighbors(x: usize, y: usize) -> [(usize, usize); 4] {
+fn generate_random_neighbors(x: usize, y: usize, rng: &mut impl Rng) -> [(usize, usize); 4] {
     let mut neighbors = get_neighbors(x, y);
 
-    let mut rng = rand::rng();
-    neighbors.shuffle(&mut rng);
+    neighbors.shuffle(rng);
 
     neighbors
 }
 
 fn is_node_next_to_visited(x: usize, y: usize, parent_x: usize, parent_y: usize,
-        maze: &Vec<Vec<bool>>, board: &Board) -> bool {
+        maze: &[Vec<bool>], board: &Board) -> bool {
     let neighbors = get_neighbors(x, y);
 
     for (x_n, y_n) in neighbors {
@@ -85,3 +87,43 @@ fn is_node_next_to_visited(x: usize, y: usize, parent_x: usize, parent_y: usize,
 
     false
 }
+
+// Places portal pairs on free cells reachable from `origin`, so both ends of every portal
+// can actually be reached. Cells from `occupied` are left alone
+pub fn place_portals(maze: &[Vec<bool>], board: &Board, pairs: u32, origin: Point,
+        occupied: &[Point], rng: &mut impl Rng) -> Vec<Portal> {
+    let mut reachable_cells = find_reachable_cells(maze, board, origin);
+    reachable_cells.retain(|cell| !occupied.contains(cell));
+    reachable_cells.shuffle(rng);
+
+    let mut portals = vec![];
+    while portals.len() < pairs as usize && reachable_cells.len() >= 2 {
+        let entrance = reachable_cells.pop().expect("Checked the length above");
+        let exit = reachable_cells.pop().expect("Checked the length above");
+        portals.push((entrance, exit));
+    }
+
+    portals
+}
+
+fn find_reachable_cells(maze: &[Vec<bool>], board: &Board, origin: Point) -> Vec<Point> {
+    let mut visited = vec![vec![false; board.get_width()]; board.get_height()];
+    let mut queue = VecDeque::from([(origin.get_x() as usize, origin.get_y() as usize)]);
+    let mut reachable_cells = vec![];
+
+    visited[origin.get_y() as usize][origin.get_x() as usize] = true;
+
+    while let Some((x, y)) = queue.pop_front() {
+        reachable_cells.push(Point::new(x as i32, y as i32));
+
+        for (x_n, y_n) in get_neighbors(x, y) {
+            if x_n < board.get_width() && y_n < board.get_height() &&
+                    !maze[y_n][x_n] && !visited[y_n][x_n] {
+                visited[y_n][x_n] = true;
+                queue.push_back((x_n, y_n));
+            }
+        }
+    }
+
+    reachable_cells
+}
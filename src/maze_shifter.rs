@@ -0,0 +1,144 @@
+use std::collections::VecDeque;
+
+use rand::{Rng, seq::IndexedRandom};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    direction::DIRECTIONS,
+    point::Point
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ShiftingMazeConfig {
+    interval: u64,
+    walls_per_shift: u32,
+    telegraph_ticks: u64
+}
+
+impl Default for ShiftingMazeConfig {
+    fn default() -> ShiftingMazeConfig {
+        ShiftingMazeConfig { interval: 0, walls_per_shift: 3, telegraph_ticks: 6 }
+    }
+}
+
+impl ShiftingMazeConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.interval > 0
+    }
+}
+
+// Slowly changes the maze during play: some walls crumble, others rise
+// after being announced on the board for a few ticks
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MazeShifter {
+    config: ShiftingMazeConfig,
+    // Cells that are about to become walls, with the tick they rise at
+    rising_walls: Vec<(Point, u64)>
+}
+
+impl MazeShifter {
+    pub fn new(config: &ShiftingMazeConfig) -> MazeShifter {
+        MazeShifter { config: config.clone(), rising_walls: vec![] }
+    }
+
+    pub fn get_rising_walls(&self) -> impl Iterator<Item = &Point> {
+        self.rising_walls.iter().map(|(point, _)| point)
+    }
+
+    // Returns whether the maze has changed. Walls never rise on `protected` cells,
+    // and never cut `origin` off from any of the `targets`
+    pub fn update(&mut self, maze: &mut [Vec<bool>], tick_count: u64, protected: &[Point],
+            origin: Point, targets: &[Point], rng: &mut impl Rng) -> bool {
+        let config = &self.config;
+        let mut has_changed = false;
+
+        let (risen, waiting): (Vec<_>, Vec<_>) = self.rising_walls.iter()
+            .partition(|(_, rises_at)| tick_count >= *rises_at);
+        self.rising_walls = waiting;
+
+        for (point, _) in risen {
+            let (x, y) = (point.get_x() as usize, point.get_y() as usize);
+            if protected.contains(&point) || maze[y][x] {
+                continue;
+            }
+
+            maze[y][x] = true;
+            if are_targets_reachable(maze, origin, targets) {
+                has_changed = true;
+            } else {
+                maze[y][x] = false;
+            }
+        }
+
+        if !config.is_enabled() || !tick_count.is_multiple_of(config.interval) {
+            return has_changed;
+        }
+
+        let (walls, free_cells) = split_cells(maze);
+
+        for wall in walls.choose_multiple(rng, config.walls_per_shift as usize) {
+            maze[wall.get_y() as usize][wall.get_x() as usize] = false;
+            has_changed = true;
+        }
+
+        let candidates: Vec<&Point> = free_cells.iter()
+            .filter(|cell| !protected.contains(cell))
+            .collect();
+        for cell in candidates.choose_multiple(rng, config.walls_per_shift as usize) {
+            self.rising_walls.push((**cell, tick_count + config.telegraph_ticks));
+        }
+
+        has_changed
+    }
+}
+
+fn split_cells(maze: &[Vec<bool>]) -> (Vec<Point>, Vec<Point>) {
+    let mut walls = vec![];
+    let mut free_cells = vec![];
+
+    for (y, row) in maze.iter().enumerate() {
+        for (x, is_wall) in row.iter().enumerate() {
+            let point = Point::new(x as i32, y as i32);
+            if *is_wall {
+                walls.push(point);
+            } else {
+                free_cells.push(point);
+            }
+        }
+    }
+
+    (walls, free_cells)
+}
+
+fn are_targets_reachable(maze: &[Vec<bool>], origin: Point, targets: &[Point]) -> bool {
+    let (height, width) = (maze.len() as i32, maze[0].len() as i32);
+    let is_free = |point: &Point| {
+        point.get_x() >= 0 && point.get_y() >= 0 && point.get_x() < width && point.get_y() < height &&
+            !maze[point.get_y() as usize][point.get_x() as usize]
+    };
+
+    let mut visited = vec![vec![false; width as usize]; height as usize];
+    let mut queue = VecDeque::from([origin]);
+    let mut found = 0;
+
+    if is_free(&origin) {
+        visited[origin.get_y() as usize][origin.get_x() as usize] = true;
+    }
+
+    while let Some(current) = queue.pop_front() {
+        if targets.contains(&current) {
+            found += 1;
+        }
+
+        for direction in DIRECTIONS {
+            let next = current.moved(direction);
+            if is_free(&next) && !visited[next.get_y() as usize][next.get_x() as usize] {
+                visited[next.get_y() as usize][next.get_x() as usize] = true;
+                queue.push_back(next);
+            }
+        }
+    }
+
+    found >= targets.len()
+}
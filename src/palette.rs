@@ -0,0 +1,185 @@
+use std::env;
+
+use crossterm::style::Color;
+use serde::{Deserialize, Serialize};
+
+// Colors the terminal can show
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ColorSupport {
+    #[default]
+    TrueColor,
+    Ansi256,
+    Ansi16,
+    None
+}
+
+// The 16 basic colors with the RGB values most terminals give them
+const ANSI_16_COLORS: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::DarkRed, (128, 0, 0)),
+    (Color::DarkGreen, (0, 128, 0)),
+    (Color::DarkYellow, (128, 128, 0)),
+    (Color::DarkBlue, (0, 0, 128)),
+    (Color::DarkMagenta, (128, 0, 128)),
+    (Color::DarkCyan, (0, 128, 128)),
+    (Color::Grey, (192, 192, 192)),
+    (Color::DarkGrey, (128, 128, 128)),
+    (Color::Red, (255, 0, 0)),
+    (Color::Green, (0, 255, 0)),
+    (Color::Yellow, (255, 255, 0)),
+    (Color::Blue, (0, 0, 255)),
+    (Color::Magenta, (255, 0, 255)),
+    (Color::Cyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255))
+];
+
+impl ColorSupport {
+    // Follows the usual conventions: NO_COLOR turns colors off, COLORTERM announces
+    // true color, and TERM tells the rest
+    pub fn detect() -> ColorSupport {
+        if env::var("NO_COLOR").is_ok_and(|value| !value.is_empty()) {
+            return ColorSupport::None;
+        }
+
+        let color_term = env::var("COLORTERM").unwrap_or_default();
+        if color_term == "truecolor" || color_term == "24bit" {
+            return ColorSupport::TrueColor;
+        }
+
+        match env::var("TERM") {
+            Ok(term) if term == "dumb" || term.is_empty() => ColorSupport::None,
+            Ok(term) if term.contains("256color") => ColorSupport::Ansi256,
+            Ok(_) => ColorSupport::Ansi16,
+            // Windows terminals don't set TERM, but they all handle colors
+            Err(_) if cfg!(windows) => ColorSupport::TrueColor,
+            Err(_) => ColorSupport::None
+        }
+    }
+
+    // The closest color the terminal can show, if any
+    pub fn adapt(&self, color: Color) -> Option<Color> {
+        match *self {
+            ColorSupport::TrueColor => Some(color),
+            ColorSupport::Ansi256 => match color {
+                Color::Rgb { r, g, b } => {
+                    let level = |channel: u8| (channel as f64 / 255.0 * 5.0).round() as u8;
+                    Some(Color::AnsiValue(16 + 36 * level(r) + 6 * level(g) + level(b)))
+                }
+                _ => Some(color)
+            },
+            ColorSupport::Ansi16 => match to_rgb(color) {
+                Some(rgb) => Some(find_closest_ansi_16(rgb)),
+                None => Some(color)
+            },
+            ColorSupport::None => None
+        }
+    }
+}
+
+// RGB values of true colors and 256-color values; the basic named colors need no conversion
+fn to_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    match color {
+        Color::Rgb { r, g, b } => Some((r, g, b)),
+        Color::AnsiValue(value) if value < 16 => Some(ANSI_16_COLORS[value as usize].1),
+        Color::AnsiValue(value) if value < 232 => {
+            let level = |index: u8| if index == 0 { 0 } else { 55 + index * 40 };
+            let index = value - 16;
+            Some((level(index / 36), level(index / 6 % 6), level(index % 6)))
+        }
+        Color::AnsiValue(value) => {
+            let grey = 8 + (value - 232) * 10;
+            Some((grey, grey, grey))
+        }
+        _ => None
+    }
+}
+
+fn find_closest_ansi_16((r, g, b): (u8, u8, u8)) -> Color {
+    let distance = |(other_r, other_g, other_b): (u8, u8, u8)| {
+        let difference = |from: u8, to: u8| (from as i32 - to as i32).pow(2);
+        difference(r, other_r) + difference(g, other_g) + difference(b, other_b)
+    };
+
+    ANSI_16_COLORS.iter()
+        .min_by_key(|(_, rgb)| distance(*rgb))
+        .map(|(color, _)| *color)
+        .expect("There are 16 colors")
+}
+
+// Color schemes for color vision deficiencies, built on the Okabe-Ito palette
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Palette {
+    // Whatever the theme says
+    #[default]
+    Theme,
+    // Red-green, the common ones
+    Deuteranopia,
+    Protanopia,
+    // Blue-yellow
+    Tritanopia,
+    // No colors at all, parts differ by glyph only
+    Monochrome
+}
+
+const ORANGE: Color = Color::Rgb { r: 0xe6, g: 0x9f, b: 0x00 };
+const SKY_BLUE: Color = Color::Rgb { r: 0x56, g: 0xb4, b: 0xe9 };
+const BLUISH_GREEN: Color = Color::Rgb { r: 0x00, g: 0x9e, b: 0x73 };
+const YELLOW: Color = Color::Rgb { r: 0xf0, g: 0xe4, b: 0x42 };
+const BLUE: Color = Color::Rgb { r: 0x00, g: 0x72, b: 0xb2 };
+const VERMILLION: Color = Color::Rgb { r: 0xd5, g: 0x5e, b: 0x00 };
+const REDDISH_PURPLE: Color = Color::Rgb { r: 0xcc, g: 0x79, b: 0xa7 };
+
+// Colors of the parts that have to tell apart at a glance
+pub struct PartColors {
+    pub wall: Color,
+    pub apple: Color,
+    pub golden: Color,
+    pub poison: Color,
+    pub speed: Color,
+    pub opponent: Color,
+    pub rising_wall: Color,
+    pub portals: [Color; 3]
+}
+
+impl Palette {
+    // None keeps the theme's colors
+    pub fn get_part_colors(&self) -> Option<PartColors> {
+        match *self {
+            Palette::Theme | Palette::Monochrome => None,
+            // Red and green look alike, so the board relies on blue against orange and yellow
+            Palette::Deuteranopia => Some(PartColors {
+                wall: BLUE,
+                apple: YELLOW,
+                golden: ORANGE,
+                poison: REDDISH_PURPLE,
+                speed: SKY_BLUE,
+                opponent: Color::White,
+                rising_wall: SKY_BLUE,
+                portals: [ORANGE, SKY_BLUE, YELLOW]
+            }),
+            // Like deuteranopia, but reds also look dark, so purple poison would fade into the floor
+            Palette::Protanopia => Some(PartColors {
+                wall: BLUE,
+                apple: YELLOW,
+                golden: ORANGE,
+                poison: SKY_BLUE,
+                speed: Color::White,
+                opponent: Color::Grey,
+                rising_wall: ORANGE,
+                portals: [YELLOW, SKY_BLUE, Color::White]
+            }),
+            // Blue and green look alike, so only one of them is used
+            Palette::Tritanopia => Some(PartColors {
+                wall: VERMILLION,
+                apple: BLUISH_GREEN,
+                golden: Color::White,
+                poison: REDDISH_PURPLE,
+                speed: ORANGE,
+                opponent: Color::Grey,
+                rising_wall: REDDISH_PURPLE,
+                portals: [VERMILLION, BLUISH_GREEN, Color::White]
+            })
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+use crate::direction::Direction;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
 pub struct Point {
     x: i32,
     y: i32,
@@ -25,6 +28,19 @@ impl Point {
         }
     }
     
+    pub fn moved(&self, direction: Direction) -> Point {
+        match direction {
+            Direction::DOWN => self.change_by_y(1),
+            Direction::UP => self.change_by_y(-1),
+            Direction::RIGHT => self.change_by_x(1),
+            Direction::LEFT => self.change_by_x(-1)
+        }
+    }
+    
+    pub fn distance_to(&self, other: &Point) -> u32 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+    
     pub fn get_x(&self) -> i32 {
         self.x
     }
@@ -34,9 +50,7 @@ impl Point {
     }
 }
 
-pub fn generate_point(width: u32, height: u32) -> Point {
-    let mut rng = rand::rng();
-    
+pub fn generate_point(width: u32, height: u32, rng: &mut impl Rng) -> Point {
     let rand_x = rng.random_range(0..width) as i32;
     let rand_y = rng.random_range(0..height) as i32;
     
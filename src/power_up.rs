@@ -0,0 +1,201 @@
+use std::mem;
+
+use rand::{Rng, seq::IndexedRandom};
+use serde::{Deserialize, Serialize};
+
+use crate::point::Point;
+
+const SLOW_MOTION_MULTIPLIER: f64 = 2.0;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerUpKind {
+    // Passes through maze walls
+    Ghost,
+    // Survives one collision
+    Shield,
+    // Pulls food toward the head
+    Magnet,
+    // Doubles the pause time
+    SlowMotion
+}
+
+impl PowerUpKind {
+    pub fn get_glyph(&self) -> char {
+        match *self {
+            PowerUpKind::Ghost => 'G',
+            PowerUpKind::Shield => 'S',
+            PowerUpKind::Magnet => 'M',
+            PowerUpKind::SlowMotion => 'T'
+        }
+    }
+
+    pub fn get_effect(&self) -> Effect {
+        match *self {
+            PowerUpKind::Ghost => Effect::Ghost,
+            PowerUpKind::Shield => Effect::Shield,
+            PowerUpKind::Magnet => Effect::Magnet,
+            PowerUpKind::SlowMotion => Effect::SlowMotion
+        }
+    }
+}
+
+// A pickup lying on the board
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct PowerUp {
+    kind: PowerUpKind,
+    position: Point,
+    expires_at: u64
+}
+
+impl PowerUp {
+    pub fn new(kind: PowerUpKind, position: Point, expires_at: u64) -> PowerUp {
+        PowerUp { kind, position, expires_at }
+    }
+
+    pub fn get_kind(&self) -> PowerUpKind {
+        self.kind
+    }
+
+    pub fn get_position(&self) -> Point {
+        self.position
+    }
+
+    pub fn has_expired(&self, tick_count: u64) -> bool {
+        tick_count >= self.expires_at
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Effect {
+    Ghost,
+    Shield,
+    Magnet,
+    SlowMotion,
+    // Left by a speed fruit, multiplies the pause time
+    Speed(f64)
+}
+
+impl Effect {
+    pub fn get_name(&self) -> &'static str {
+        match *self {
+            Effect::Ghost => "ghost",
+            Effect::Shield => "shield",
+            Effect::Magnet => "magnet",
+            Effect::SlowMotion => "slow-mo",
+            Effect::Speed(multiplier) if multiplier > 1.0 => "slowed",
+            Effect::Speed(_) => "hasted"
+        }
+    }
+}
+
+// Timed effects the player currently has, each with the tick it wears off at
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Effects {
+    active: Vec<(Effect, u64)>
+}
+
+impl Effects {
+    // Taking the same effect again just refreshes it
+    pub fn activate(&mut self, effect: Effect, expires_at: u64) {
+        self.active.retain(|(active, _)| mem::discriminant(active) != mem::discriminant(&effect));
+        self.active.push((effect, expires_at));
+    }
+
+    pub fn is_active(&self, effect: Effect) -> bool {
+        self.active.iter().any(|(active, _)| *active == effect)
+    }
+
+    pub fn remove(&mut self, effect: Effect) {
+        self.active.retain(|(active, _)| *active != effect);
+    }
+
+    pub fn expire(&mut self, tick_count: u64) {
+        self.active.retain(|(_, expires_at)| tick_count < *expires_at);
+    }
+
+    pub fn get_pause_multiplier(&self) -> f64 {
+        self.active.iter()
+            .map(|(effect, _)| match *effect {
+                Effect::SlowMotion => SLOW_MOTION_MULTIPLIER,
+                Effect::Speed(multiplier) => multiplier,
+                _ => 1.0
+            })
+            .product()
+    }
+
+    // Every active effect with how many ticks it has left
+    pub fn get_remaining(&self, tick_count: u64) -> Vec<(Effect, u64)> {
+        self.active.iter()
+            .map(|(effect, expires_at)| (*effect, expires_at.saturating_sub(tick_count)))
+            .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct PowerUpConfig {
+    chance: f64,
+    lifetime: u64,
+    duration: u64,
+    magnet_radius: u32,
+    weights: PowerUpWeights
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct PowerUpWeights {
+    ghost: u32,
+    shield: u32,
+    magnet: u32,
+    slow_motion: u32
+}
+
+impl Default for PowerUpConfig {
+    fn default() -> PowerUpConfig {
+        PowerUpConfig {
+            chance: 0.0,
+            lifetime: 80,
+            duration: 60,
+            magnet_radius: 8,
+            weights: PowerUpWeights::default()
+        }
+    }
+}
+
+impl Default for PowerUpWeights {
+    fn default() -> PowerUpWeights {
+        PowerUpWeights { ghost: 1, shield: 1, magnet: 1, slow_motion: 1 }
+    }
+}
+
+impl PowerUpConfig {
+    pub fn get_chance(&self) -> f64 {
+        self.chance
+    }
+
+    pub fn get_lifetime(&self) -> u64 {
+        self.lifetime
+    }
+
+    pub fn get_duration(&self) -> u64 {
+        self.duration
+    }
+
+    pub fn get_magnet_radius(&self) -> u32 {
+        self.magnet_radius
+    }
+
+    pub fn pick_kind(&self, rng: &mut impl Rng) -> Option<PowerUpKind> {
+        let weights = [
+            (PowerUpKind::Ghost, self.weights.ghost),
+            (PowerUpKind::Shield, self.weights.shield),
+            (PowerUpKind::Magnet, self.weights.magnet),
+            (PowerUpKind::SlowMotion, self.weights.slow_motion)
+        ];
+
+        weights.choose_weighted(rng, |(_, weight)| *weight)
+            .ok()
+            .map(|(kind, _)| *kind)
+    }
+}
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+// How board cells map onto terminal characters, which are about twice as tall as they are wide
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderMode {
+    // One character per cell, looks stretched vertically
+    Single,
+    // Two characters per cell
+    #[default]
+    DoubleWidth,
+    // Two rows of cells packed into one line of `▀`/`▄` blocks, colors only
+    HalfBlock
+}
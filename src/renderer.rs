@@ -0,0 +1,208 @@
+use std::io::{self, Write, stdout};
+
+use crossterm::{
+    style::{Color, Print, Stylize},
+    terminal::{Clear, ClearType}
+};
+
+use crate::{
+    render_mode::RenderMode,
+    terminal_handler,
+    theme::{FrameStyle, Look}
+};
+
+// Everything one screen of the game shows, built once per tact and handed to a renderer
+#[derive(Clone, Default)]
+pub struct Frame {
+    // The field as plain glyphs, one per cell
+    cells: Vec<Vec<char>>,
+    // How every cell looks in the theme, with what's out of sight hidden
+    looks: Vec<Vec<Look>>,
+    hud: Vec<String>,
+    // Shown below the HUD only when asked for, like the debug overlay
+    overlays: Vec<String>
+}
+
+impl Frame {
+    pub fn new(cells: Vec<Vec<char>>, looks: Vec<Vec<Look>>, hud: Vec<String>, overlays: Vec<String>) -> Frame {
+        Frame { cells, looks, hud, overlays }
+    }
+
+    pub fn get_cells(&self) -> &[Vec<char>] {
+        &self.cells
+    }
+
+    pub fn get_looks(&self) -> &[Vec<Look>] {
+        &self.looks
+    }
+
+    pub fn get_hud(&self) -> &[String] {
+        &self.hud
+    }
+
+    pub fn get_overlays(&self) -> &[String] {
+        &self.overlays
+    }
+}
+
+pub trait Renderer {
+    fn render(&mut self, frame: &Frame) -> io::Result<()>;
+}
+
+// Paints the looks in colors and draws over the previous frame in place, so the screen doesn't flicker
+pub struct TerminalRenderer {
+    render_mode: RenderMode,
+    frame_style: FrameStyle
+}
+
+impl TerminalRenderer {
+    pub fn new(render_mode: RenderMode, frame_style: FrameStyle) -> TerminalRenderer {
+        TerminalRenderer { render_mode, frame_style }
+    }
+
+    // The painted board inside its frame, line by line
+    fn paint_board(&self, looks: &[Vec<Look>]) -> Vec<String> {
+        let width = looks.first().map_or(0, Vec::len);
+        let rows: Vec<String> = match self.render_mode {
+            RenderMode::Single | RenderMode::DoubleWidth => {
+                let is_double_width = self.render_mode == RenderMode::DoubleWidth;
+                looks.iter()
+                    .map(|row| row.iter().map(|look| look.paint(is_double_width)).collect())
+                    .collect()
+            }
+            RenderMode::HalfBlock => looks.chunks(2)
+                .map(|rows| (0..width)
+                    .map(|x| {
+                        let top = rows[0][x].get_color();
+                        let bottom = rows.get(1).and_then(|row| row[x].get_color());
+                        paint_half_blocks(top, bottom)
+                    })
+                    .collect())
+                .collect()
+        };
+        let frame_width = match self.render_mode {
+            RenderMode::DoubleWidth => width * 2,
+            RenderMode::Single | RenderMode::HalfBlock => width
+        };
+
+        let mut board = vec![self.frame_style.get_top(frame_width)];
+        for row in rows {
+            board.push(format!("{}{}{}", self.frame_style.get_side(), row, self.frame_style.get_side()));
+        }
+        board.push(self.frame_style.get_bottom(frame_width));
+
+        board
+    }
+}
+
+impl Renderer for TerminalRenderer {
+    fn render(&mut self, frame: &Frame) -> io::Result<()> {
+        let board = self.paint_board(frame.get_looks());
+        terminal_handler::reset_cursor_position()?;
+        let mut stdout = stdout().lock();
+
+        for line in &board {
+            crossterm::queue!(stdout, Print(line), Print("\r\n"))?;
+        }
+        crossterm::queue!(stdout, Clear(ClearType::UntilNewLine), Print("\r\n"))?;
+        // Lines of the previous frame may be longer, or more of them
+        for line in frame.get_hud().iter().chain(frame.get_overlays()) {
+            crossterm::queue!(stdout, Print(line), Clear(ClearType::UntilNewLine), Print("\r\n"))?;
+        }
+        crossterm::queue!(stdout, Clear(ClearType::FromCursorDown))?;
+
+        stdout.flush()
+    }
+}
+
+// One character showing two cells, the upper one in the foreground
+fn paint_half_blocks(top: Option<Color>, bottom: Option<Color>) -> String {
+    match (top, bottom) {
+        (None, None) => String::from(" "),
+        (Some(top), None) => format!("{}", '▀'.with(top)),
+        (None, Some(bottom)) => format!("{}", '▄'.with(bottom)),
+        (Some(top), Some(bottom)) => format!("{}", '▀'.with(top).on(bottom))
+    }
+}
+
+// Draws the cells in plain ASCII with no colors or themes, for logs and for comparing frames
+pub struct PlainRenderer<W: Write> {
+    output: W
+}
+
+impl<W: Write> PlainRenderer<W> {
+    pub fn new(output: W) -> PlainRenderer<W> {
+        PlainRenderer { output }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.output
+    }
+}
+
+impl<W: Write> Renderer for PlainRenderer<W> {
+    fn render(&mut self, frame: &Frame) -> io::Result<()> {
+        let width = frame.get_cells().first().map_or(0, Vec::len);
+        let border = format!("+{}+", "-".repeat(width));
+
+        writeln!(self.output, "{border}")?;
+        for row in frame.get_cells() {
+            writeln!(self.output, "|{}|", String::from_iter(row))?;
+        }
+        writeln!(self.output, "{border}")?;
+        for line in frame.get_hud().iter().chain(frame.get_overlays()) {
+            writeln!(self.output, "{line}")?;
+        }
+
+        self.output.flush()
+    }
+}
+
+// Draws nothing, for games played without a screen
+pub struct NullRenderer;
+
+impl Renderer for NullRenderer {
+    fn render(&mut self, _frame: &Frame) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// Games start out headless, only playing one in the terminal draws it
+pub fn default_renderer() -> Box<dyn Renderer> {
+    Box::new(NullRenderer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Config, game::Game};
+
+    #[test]
+    fn plain_frame_matches_snapshot() {
+        let mut game = Game::with_seed(Config::new(16, 8, 100, false, 0.0), 42);
+        for _ in 0..3 {
+            game.tick(None);
+        }
+
+        let mut renderer = PlainRenderer::new(Vec::new());
+        renderer.render(&game.build_frame()).unwrap();
+        let output = String::from_utf8(renderer.into_inner()).unwrap();
+
+        let expected = "\
++----------------+
+|                |
+|                |
+|                |
+|                |
+|          *@    |
+|        $       |
+|                |
+|                |
++----------------+
+Your score: 0    Speed: 10.0 tacts/s
+Effects: none
+
+";
+        assert_eq!(output, expected);
+    }
+}
@@ -0,0 +1,54 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct RewindConfig {
+    // How far back in game time the player can go
+    seconds: u64,
+    // Score taken for every rewind
+    penalty: u32
+}
+
+impl Default for RewindConfig {
+    fn default() -> RewindConfig {
+        RewindConfig { seconds: 5, penalty: 1 }
+    }
+}
+
+impl RewindConfig {
+    pub fn get_penalty(&self) -> u32 {
+        self.penalty
+    }
+}
+
+// Snapshots of the last few seconds of a game, oldest first
+pub struct Rewind<T> {
+    limit: u64,
+    snapshots: VecDeque<(u64, T)>
+}
+
+impl<T> Rewind<T> {
+    pub fn new(config: &RewindConfig) -> Rewind<T> {
+        Rewind { limit: config.seconds * 1000, snapshots: VecDeque::new() }
+    }
+
+    // `elapsed_time` is the game time of the snapshot in milliseconds
+    pub fn record(&mut self, elapsed_time: u64, snapshot: T) {
+        self.snapshots.push_back((elapsed_time, snapshot));
+        while let Some((time, _)) = self.snapshots.front()
+            && *time + self.limit < elapsed_time {
+            self.snapshots.pop_front();
+        }
+    }
+
+    // The latest snapshot, which is gone from the history afterwards
+    pub fn step_back(&mut self) -> Option<T> {
+        self.snapshots.pop_back().map(|(_, snapshot)| snapshot)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+}
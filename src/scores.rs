@@ -0,0 +1,49 @@
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use crate::storage;
+
+const FILENAME: &str = "scores.json";
+
+// Finished games kept locally, newest last
+#[derive(Serialize, Deserialize, Default)]
+pub struct ScoreStore {
+    entries: Vec<ScoreEntry>
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ScoreEntry {
+    // UTC date as YYYY-MM-DD
+    date: String,
+    mode: String,
+    score: u32
+}
+
+impl ScoreEntry {
+    pub fn new(date: String, mode: &str, score: u32) -> ScoreEntry {
+        ScoreEntry { date, mode: mode.to_string(), score }
+    }
+
+    pub fn get_score(&self) -> u32 {
+        self.score
+    }
+}
+
+impl ScoreStore {
+    pub fn load() -> ScoreStore {
+        storage::load(FILENAME)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        storage::save(FILENAME, self)
+    }
+
+    pub fn record(&mut self, entry: ScoreEntry) {
+        self.entries.push(entry);
+    }
+
+    pub fn find(&self, mode: &str, date: &str) -> Option<&ScoreEntry> {
+        self.entries.iter().find(|entry| entry.mode == mode && entry.date == date)
+    }
+}
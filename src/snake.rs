@@ -1,52 +1,54 @@
 use std::collections::VecDeque;
 
+use serde::{Deserialize, Serialize};
+
 use crate::point::Point;
 use crate::direction::Direction;
 
 pub const SNAKE_INIT_SIZE: u32 = 2;
 
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Snake {
     segments: VecDeque<Point>,
-    direction: Direction
+    direction: Direction,
+    // Legacy aspect compensation, see `take_step`
+    is_vertical_debt_enabled: bool,
+    has_vertical_debt: bool
 }
 
 impl Snake {
     pub fn from_center(center: Point) -> Snake {
-        Snake {
-            segments: VecDeque::from(vec![center, center.change_by_x(-1)]),
-            direction: Direction::RIGHT
-        }
+        Snake::from_segments(vec![center, center.change_by_x(-1)])
     }
     
     pub fn from_segments(segments: Vec<Point>) -> Snake {
         Snake {
             segments: VecDeque::from(segments),
-            direction: Direction::RIGHT
+            direction: Direction::RIGHT,
+            is_vertical_debt_enabled: false,
+            has_vertical_debt: true
         }
     }
     
-    pub fn is_self_collision(&self) -> bool {
-        self.segments.iter().skip(1).any(|segment| segment == self.get_head())
+    pub fn enable_vertical_debt(&mut self) {
+        self.is_vertical_debt_enabled = true;
+    }
+    
+    // None when the vertical debt is off, otherwise whether the next vertical step is skipped
+    pub fn get_vertical_debt(&self) -> Option<bool> {
+        self.is_vertical_debt_enabled.then_some(self.has_vertical_debt)
+    }
+    
+    // The index of the segment the head has run into, if any
+    pub fn find_bitten_segment(&self) -> Option<usize> {
+        self.segments.iter()
+            .skip(1)
+            .position(|segment| segment == self.get_head())
+            .map(|index| index + 1)
     }
     
     fn validate_direction_change(&self, direction: Direction) -> bool {
-        match (self.get_direction(), direction) {
-            (Direction::LEFT, Direction::RIGHT) => {
-                false
-            }
-            (Direction::RIGHT, Direction::LEFT) => {
-                false
-            }
-            (Direction::UP, Direction::DOWN) => {
-                false
-            }
-            (Direction::DOWN, Direction::UP) => {
-                false
-            }
-            _ => {
-                true
-            }
-        }
+        self.get_direction().opposite() != direction
     }
     
     pub fn change_direction(&mut self, direction: Direction) {
@@ -62,39 +64,52 @@ impl Snake {
         self.segments.pop_back();
     }
     
+    pub fn advance(&mut self) {
+        if self.take_step() {
+            self.move_forward();
+        }
+    }
+    
+    // Like `advance`, but the tail stays where it is
+    pub fn stretch(&mut self) {
+        if self.take_step() {
+            self.grow();
+        }
+    }
+    
+    // Terminal cells are about twice as tall as they are wide, so with the vertical debt
+    // vertical movement only happens every other tick to keep the speed visually even
+    fn take_step(&mut self) -> bool {
+        if !self.is_vertical_debt_enabled {
+            true
+        } else if self.direction.is_vertical() {
+            self.has_vertical_debt = !self.has_vertical_debt;
+            self.has_vertical_debt
+        } else {
+            self.has_vertical_debt = false;
+            true
+        }
+    }
+    
     pub fn grow(&mut self) {
         let head = self.segments.front().expect("Snake has no body!");
-        
-        let new_head = match self.direction {
-            Direction::DOWN => {
-                Point::new(
-                    head.get_x(),
-                    head.get_y() + 1
-                )
-            }
-            Direction::UP => {
-                Point::new(
-                    head.get_x(),
-                    head.get_y() - 1
-                )
-            }
-            Direction::RIGHT => {
-                Point::new(
-                    head.get_x() + 1,
-                    head.get_y()
-                )
-            }
-            Direction::LEFT => {
-                Point::new(
-                    head.get_x() - 1,
-                    head.get_y()
-                )
-            }
-        };
+        let new_head = head.moved(self.direction);
         
         self.segments.push_front(new_head);
     }
     
+    pub fn teleport_head(&mut self, point: Point) {
+        if let Some(head) = self.segments.front_mut() {
+            *head = point;
+        }
+    }
+    
+    // Drops `count` segments off the tail, but never goes below `min_len`
+    pub fn shrink(&mut self, count: usize, min_len: usize) {
+        let new_len = self.segments.len().saturating_sub(count).max(min_len);
+        self.segments.truncate(new_len);
+    }
+    
     pub fn get_segments(&self) -> &VecDeque<Point> {
         &self.segments
     }
@@ -106,4 +121,8 @@ impl Snake {
     pub fn get_direction(&self) -> Direction {
         self.direction
     }
+    
+    pub fn get_len(&self) -> usize {
+        self.segments.len()
+    }
 }
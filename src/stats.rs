@@ -0,0 +1,176 @@
+use std::collections::BTreeMap;
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    death_cause::DeathCause,
+    events::{GameEvent, Observer},
+    storage
+};
+
+const FILENAME: &str = "stats.json";
+
+// How many columns the longest bar of a histogram takes
+const BAR_WIDTH: usize = 40;
+const SCORE_BUCKETS: u32 = 10;
+
+// What happened during one game. The counters run along with the game,
+// the rest is filled in once it's over
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct GameStats {
+    // Board and mode the game was played with, games are grouped by it
+    setup: String,
+    score: u32,
+    ticks: u64,
+    // Game time in milliseconds
+    elapsed_time: u64,
+    apples: u32,
+    turns: u32,
+    cells_travelled: u64,
+    // Cells travelled without turning, the longest run so far and the current one
+    longest_straight: u64,
+    current_straight: u64,
+    death_cause: Option<DeathCause>
+}
+
+impl Observer for GameStats {
+    fn on_event(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::AppleEaten { kind } if kind.is_edible() => self.apples += 1,
+            GameEvent::DirectionChanged { .. } => {
+                self.turns += 1;
+                self.current_straight = 0;
+            }
+            GameEvent::SnakeMoved { .. } => {
+                self.cells_travelled += 1;
+                self.current_straight += 1;
+                self.longest_straight = self.longest_straight.max(self.current_straight);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl GameStats {
+    pub fn finish(&mut self, setup: String, score: u32, ticks: u64, elapsed_time: u64, death_cause: Option<DeathCause>) {
+        self.setup = setup;
+        self.score = score;
+        self.ticks = ticks;
+        self.elapsed_time = elapsed_time;
+        self.death_cause = death_cause;
+    }
+
+    pub fn describe(&self) -> String {
+        format!("Apples: {} ({:.1}/min)    Turns: {}    Cells travelled: {}    Longest straight: {} cells",
+            self.apples, get_apples_per_minute(self.apples, self.elapsed_time),
+            self.turns, self.cells_travelled, self.longest_straight)
+    }
+}
+
+// Every finished game, oldest first
+#[derive(Serialize, Deserialize, Default)]
+pub struct StatsStore {
+    games: Vec<GameStats>
+}
+
+impl StatsStore {
+    pub fn load() -> StatsStore {
+        storage::load(FILENAME)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        storage::save(FILENAME, self)
+    }
+
+    pub fn record(&mut self, game: GameStats) {
+        self.games.push(game);
+    }
+}
+
+// `snake stats` shows lifetime totals, then scores and death causes for every setup played
+pub fn run() -> io::Result<()> {
+    let store = StatsStore::load();
+    if store.games.is_empty() {
+        println!("No games played yet");
+        return Ok(());
+    }
+
+    print_totals(&store.games);
+
+    let mut setups: BTreeMap<&str, Vec<&GameStats>> = BTreeMap::new();
+    for game in &store.games {
+        setups.entry(&game.setup).or_default().push(game);
+    }
+
+    for (setup, games) in setups {
+        println!();
+        println!("{} ({} games)", setup, games.len());
+
+        let max_score = games.iter().map(|game| game.score).max().unwrap_or_default();
+        let bucket_size = max_score / SCORE_BUCKETS + 1;
+        let mut scores = vec![0; (max_score / bucket_size + 1) as usize];
+        for game in &games {
+            scores[(game.score / bucket_size) as usize] += 1;
+        }
+        let score_rows: Vec<(String, usize)> = scores.into_iter()
+            .enumerate()
+            .map(|(index, count)| {
+                let from = index as u32 * bucket_size;
+                let label = if bucket_size == 1 { from.to_string() } else { format!("{}-{}", from, from + bucket_size - 1) };
+                (label, count)
+            })
+            .collect();
+        println!("  Scores");
+        print_histogram(&score_rows);
+
+        let mut causes: BTreeMap<&str, usize> = BTreeMap::new();
+        for game in &games {
+            *causes.entry(game.death_cause.map_or("survived", |cause| cause.get_name())).or_default() += 1;
+        }
+        let cause_rows: Vec<(String, usize)> = causes.into_iter()
+            .map(|(cause, count)| (cause.to_string(), count))
+            .collect();
+        println!("  Endings");
+        print_histogram(&cause_rows);
+    }
+
+    Ok(())
+}
+
+fn print_totals(games: &[GameStats]) {
+    let elapsed_time: u64 = games.iter().map(|game| game.elapsed_time).sum();
+    let apples: u32 = games.iter().map(|game| game.apples).sum();
+    let scores: u32 = games.iter().map(|game| game.score).sum();
+    let seconds = elapsed_time / 1000;
+
+    println!("Games played: {}", games.len());
+    println!("Time played: {}:{:02}:{:02} ({} tacts)", seconds / 3600, seconds / 60 % 60, seconds % 60,
+        games.iter().map(|game| game.ticks).sum::<u64>());
+    println!("Apples eaten: {} ({:.1}/min)", apples, get_apples_per_minute(apples, elapsed_time));
+    println!("Turns made: {}", games.iter().map(|game| game.turns).sum::<u32>());
+    println!("Cells travelled: {}", games.iter().map(|game| game.cells_travelled).sum::<u64>());
+    println!("Longest straight: {} cells", games.iter().map(|game| game.longest_straight).max().unwrap_or_default());
+    println!("Best score: {}    Average score: {:.1}",
+        games.iter().map(|game| game.score).max().unwrap_or_default(),
+        scores as f64 / games.len() as f64);
+}
+
+// Bars are scaled so the biggest count fills the whole width
+fn print_histogram(rows: &[(String, usize)]) {
+    let max_count = rows.iter().map(|(_, count)| *count).max().unwrap_or_default().max(1);
+    let label_width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or_default();
+    let count_width = max_count.to_string().len();
+
+    for (label, count) in rows {
+        let bar = "#".repeat((count * BAR_WIDTH).div_ceil(max_count));
+        println!("    {:<label_width$}  {:>count_width$} {}", label, count, bar);
+    }
+}
+
+fn get_apples_per_minute(apples: u32, elapsed_time: u64) -> f64 {
+    if elapsed_time == 0 {
+        return 0.0;
+    }
+    apples as f64 * 60_000.0 / elapsed_time as f64
+}
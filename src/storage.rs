@@ -0,0 +1,32 @@
+use std::{fs, io};
+
+use serde::{Serialize, de::DeserializeOwned};
+
+// Small bits of local data (progress, results) are kept as JSON files next to the config;
+// a missing file just means nothing has been saved yet. A broken one is moved aside, so it
+// isn't overwritten, and treated as missing
+pub fn load<T: DeserializeOwned + Default>(filename: &str) -> T {
+    let Ok(data) = fs::read_to_string(filename) else {
+        return T::default();
+    };
+
+    serde_json::from_str(&data).unwrap_or_else(|error| {
+        let corrupt_filename = format!("{filename}.corrupt");
+        let _ = fs::rename(filename, &corrupt_filename);
+        eprintln!("Failed to parse {filename} ({error}), starting over. It's kept as {corrupt_filename}");
+        T::default()
+    })
+}
+
+pub fn save<T: Serialize>(filename: &str, data: &T) -> io::Result<()> {
+    let data = serde_json::to_string_pretty(data)?;
+    fs::write(filename, data)
+}
+
+// Nothing to remove is fine too
+pub fn remove(filename: &str) -> io::Result<()> {
+    match fs::remove_file(filename) {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+        _ => Ok(())
+    }
+}
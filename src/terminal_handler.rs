@@ -1,6 +1,6 @@
 use std::time::{Duration};
 use std::io::{self, stdout};
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyCode, KeyEvent};
 use crossterm::{
     cursor,
     terminal::{self, ClearType},
@@ -39,8 +39,17 @@ pub fn clear_screen() -> io::Result<()> {
 }
 
 pub fn get_key_event() -> io::Result<Option<KeyEvent>> {
-    if poll(Duration::from_millis(0))? {
-        if let Event::Key(key_event) = read()? {
+    if poll(Duration::from_millis(0))? && let Event::Key(key_event) = read()? {
+        return Ok(Some(key_event));
+    }
+    Ok(None)
+}
+
+// Drops the queued presses of `code`, like the ones piling up while a key is held down.
+// Returns the first other key, if one comes in the meantime
+pub fn skip_key_repeats(code: KeyCode) -> io::Result<Option<KeyEvent>> {
+    while let Some(key_event) = get_key_event()? {
+        if key_event.code != code {
             return Ok(Some(key_event));
         }
     }
@@ -0,0 +1,490 @@
+use std::fs;
+
+use crossterm::style::{Color, Stylize};
+use serde::Deserialize;
+
+use crate::{
+    direction::Direction,
+    palette::{ColorSupport, Palette}
+};
+
+const BOX_THEME: &str = include_str!("../themes/box.toml");
+const EMOJI_THEME: &str = include_str!("../themes/emoji.toml");
+const HIGH_CONTRAST_THEME: &str = include_str!("../themes/high_contrast.toml");
+const CONNECTED_WALL_GLYPHS: [&str; 12] = ["■", "│", "─", "┘", "└", "┐", "┌", "┤", "├", "┴", "┬", "┼"];
+
+// A color written in a theme, either a name like "dark_red" or "#rrggbb"
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(try_from = "String")]
+pub struct ThemeColor(Color);
+
+impl TryFrom<String> for ThemeColor {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<ThemeColor, String> {
+        if let Some(hex) = name.strip_prefix('#') && hex.len() == 6 {
+            let channel = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16)
+                .map_err(|_| format!("invalid color {name}"));
+            return Ok(ThemeColor(Color::Rgb { r: channel(0)?, g: channel(2)?, b: channel(4)? }));
+        }
+
+        Color::try_from(name.as_str())
+            .map(ThemeColor)
+            .map_err(|_| format!("unknown color {name}"))
+    }
+}
+
+// How one part of the board is drawn; a missing glyph keeps the part's own, like a portal's digit
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Style {
+    glyph: Option<String>,
+    color: Option<ThemeColor>,
+    bold: bool,
+    // The glyph takes two columns, like emoji do; only fits the double_width render mode
+    wide: bool
+}
+
+impl Style {
+    fn new(glyph: &str, color: Option<Color>) -> Style {
+        Style { glyph: Some(glyph.to_string()), color: color.map(ThemeColor), ..Style::default() }
+    }
+
+    fn colored(color: Color) -> Style {
+        Style { color: Some(ThemeColor(color)), ..Style::default() }
+    }
+
+    fn bold(self) -> Style {
+        Style { bold: true, ..self }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct FrameStyle {
+    horizontal: String,
+    vertical: String,
+    top_left: String,
+    top_right: String,
+    bottom_left: String,
+    bottom_right: String,
+    color: Option<ThemeColor>
+}
+
+impl Default for FrameStyle {
+    fn default() -> FrameStyle {
+        FrameStyle {
+            horizontal: String::from("-"),
+            vertical: String::from("|"),
+            top_left: String::from("-"),
+            top_right: String::from("-"),
+            bottom_left: String::from("-"),
+            bottom_right: String::from("-"),
+            color: None
+        }
+    }
+}
+
+impl FrameStyle {
+    pub fn get_top(&self, width: usize) -> String {
+        self.paint(format!("{}{}{}", self.top_left, self.horizontal.repeat(width), self.top_right))
+    }
+
+    pub fn get_bottom(&self, width: usize) -> String {
+        self.paint(format!("{}{}{}", self.bottom_left, self.horizontal.repeat(width), self.bottom_right))
+    }
+
+    pub fn get_side(&self) -> String {
+        self.paint(self.vertical.clone())
+    }
+
+    fn paint(&self, text: String) -> String {
+        match self.color {
+            Some(ThemeColor(color)) => format!("{}", text.with(color)),
+            None => text
+        }
+    }
+}
+
+// Glyphs and colors for everything on the board. Any part a theme file leaves out looks classic
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Theme {
+    head: Style,
+    body: Style,
+    tail: Style,
+    // The body fades from the body color into this one toward the tail; both have to be #rrggbb
+    body_gradient: Option<ThemeColor>,
+    wall: Style,
+    // Draws walls as box-drawing lines joined with their neighbors
+    connected_walls: bool,
+    remembered_wall: Style,
+    floor: Style,
+    fog: Style,
+    apple: Style,
+    golden: Style,
+    poison: Style,
+    speed: Style,
+    power_up: Style,
+    portal: Style,
+    // Portal pairs cycle through these when the portal style has no color
+    portal_colors: Vec<ThemeColor>,
+    opponent_head: Style,
+    opponent_body: Style,
+    hint: Style,
+    rising_wall: Style,
+    // The best previous run raced against
+    ghost: Style,
+    // Marks where the player died
+    fatal: Style,
+    frame: FrameStyle,
+    #[serde(skip)]
+    color_support: ColorSupport
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme {
+            head: Style::new("@", Some(Color::White)),
+            body: Style::new("*", Some(Color::Grey)),
+            tail: Style::new("*", Some(Color::Grey)),
+            body_gradient: None,
+            wall: Style::new("#", Some(Color::DarkRed)),
+            connected_walls: false,
+            remembered_wall: Style::new("#", Some(Color::DarkGrey)),
+            floor: Style::new(" ", None),
+            fog: Style::new(" ", None),
+            apple: Style::new("$", Some(Color::Green)),
+            golden: Style::new("%", Some(Color::Yellow)),
+            poison: Style::new("!", Some(Color::Magenta)),
+            speed: Style::new(">", Some(Color::Cyan)),
+            power_up: Style::colored(Color::White).bold(),
+            portal: Style::default().bold(),
+            portal_colors: [Color::Cyan, Color::Yellow, Color::Magenta, Color::Green, Color::Red]
+                .into_iter()
+                .map(ThemeColor)
+                .collect(),
+            opponent_head: Style::new("O", Some(Color::Blue)),
+            opponent_body: Style::new("o", Some(Color::Blue)),
+            hint: Style::new(".", Some(Color::DarkGrey)),
+            rising_wall: Style::colored(Color::DarkYellow),
+            ghost: Style::new("~", Some(Color::DarkGrey)),
+            fatal: Style::new("X", Some(Color::Red)).bold(),
+            frame: FrameStyle::default(),
+            color_support: ColorSupport::default()
+        }
+    }
+}
+
+// How a single cell ends up on the screen
+#[derive(Clone)]
+pub struct Look {
+    glyph: String,
+    // Second character of the cell in the double_width render mode
+    filler: String,
+    color: Option<Color>,
+    is_bold: bool
+}
+
+impl Look {
+    pub fn paint(&self, is_double_width: bool) -> String {
+        let text = if is_double_width { format!("{}{}", self.glyph, self.filler) } else { self.glyph.clone() };
+        let styled = match self.color {
+            Some(color) => text.with(color),
+            None => text.stylize()
+        };
+
+        if self.is_bold {
+            format!("{}", styled.bold())
+        } else {
+            format!("{}", styled)
+        }
+    }
+
+    // Empty cells have no color, so half-block rendering leaves them blank
+    pub fn get_color(&self) -> Option<Color> {
+        self.color
+    }
+}
+
+impl Theme {
+    // One of the built-in themes, or a path to a theme file
+    pub fn load(name: &str) -> Theme {
+        Theme::try_load(name).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_load(name: &str) -> Result<Theme, String> {
+        let theme_data = match name {
+            "classic" => return Ok(Theme::default()),
+            "box" => BOX_THEME.to_string(),
+            "emoji" => EMOJI_THEME.to_string(),
+            "high_contrast" => HIGH_CONTRAST_THEME.to_string(),
+            _ => fs::read_to_string(name)
+                .map_err(|_| format!("Failed to read theme {name}. Check it again"))?
+        };
+
+        toml::from_str(&theme_data)
+            .map_err(|error| format!("Failed to parse theme {name}: {error}"))
+    }
+
+    pub fn with_palette(mut self, palette: Palette) -> Theme {
+        if palette == Palette::Monochrome {
+            self.remove_colors();
+        }
+
+        if let Some(colors) = palette.get_part_colors() {
+            self.wall.color = Some(ThemeColor(colors.wall));
+            self.apple.color = Some(ThemeColor(colors.apple));
+            self.golden.color = Some(ThemeColor(colors.golden));
+            self.poison.color = Some(ThemeColor(colors.poison));
+            self.speed.color = Some(ThemeColor(colors.speed));
+            self.opponent_head.color = Some(ThemeColor(colors.opponent));
+            self.opponent_body.color = Some(ThemeColor(colors.opponent));
+            self.rising_wall.color = Some(ThemeColor(colors.rising_wall));
+            self.portal.color = None;
+            self.portal_colors = colors.portals.into_iter().map(ThemeColor).collect();
+        }
+
+        self
+    }
+
+    pub fn with_color_support(mut self, color_support: ColorSupport) -> Theme {
+        if color_support == ColorSupport::None {
+            self.remove_colors();
+        } else if self.has_colors() {
+            self.color_support = color_support;
+            self.frame.color = self.frame.color.and_then(|ThemeColor(color)| color_support.adapt(color).map(ThemeColor));
+        }
+
+        self
+    }
+
+    pub fn has_colors(&self) -> bool {
+        self.color_support != ColorSupport::None
+    }
+
+    // Parts only told apart by color go back to their classic glyphs, and remembered walls,
+    // which can't be dimmed anymore, get a glyph of their own. Rising walls always blink
+    fn remove_colors(&mut self) {
+        self.color_support = ColorSupport::None;
+        self.frame.color = None;
+        self.rising_wall.glyph = None;
+        self.rising_wall.wide = false;
+        if self.connected_walls {
+            self.wall.glyph = None;
+        }
+
+        let classic = Theme::default();
+        let glyphs = |glyphs: &[&str]| glyphs.iter().map(ToString::to_string).collect::<Vec<String>>();
+        let wall_glyphs = if self.connected_walls { glyphs(&CONNECTED_WALL_GLYPHS) } else { glyphs(&["#"]) };
+        let digits: Vec<String> = ('0'..='9').map(String::from).collect();
+        // Each part with its classic glyph, what it shows without a glyph and its group: the body
+        // and tail may look alike, and so may the floor and the fog
+        let mut parts = [
+            (&mut self.head, classic.head.glyph, glyphs(&["@"]), 0),
+            (&mut self.body, classic.body.glyph, glyphs(&["*"]), 1),
+            (&mut self.tail, classic.tail.glyph, glyphs(&["*"]), 1),
+            (&mut self.wall, (!self.connected_walls).then_some(String::from("#")), wall_glyphs, 2),
+            (&mut self.remembered_wall, Some(String::from("░")), glyphs(&["#"]), 3),
+            (&mut self.floor, classic.floor.glyph, glyphs(&[" "]), 4),
+            (&mut self.fog, classic.fog.glyph, glyphs(&[" "]), 4),
+            (&mut self.apple, classic.apple.glyph, glyphs(&["$"]), 5),
+            (&mut self.golden, classic.golden.glyph, glyphs(&["%"]), 6),
+            (&mut self.poison, classic.poison.glyph, glyphs(&["!"]), 7),
+            (&mut self.speed, classic.speed.glyph, glyphs(&[">"]), 8),
+            (&mut self.power_up, classic.power_up.glyph, glyphs(&["G", "S", "M", "T"]), 9),
+            (&mut self.portal, classic.portal.glyph, digits, 10),
+            (&mut self.opponent_head, classic.opponent_head.glyph, glyphs(&["O"]), 11),
+            (&mut self.opponent_body, classic.opponent_body.glyph, glyphs(&["o"]), 12),
+            (&mut self.hint, classic.hint.glyph, glyphs(&["."]), 13),
+            (&mut self.rising_wall, None, glyphs(&["+", "x"]), 14),
+            (&mut self.ghost, classic.ghost.glyph, glyphs(&["~"]), 15),
+            (&mut self.fatal, classic.fatal.glyph, glyphs(&["X"]), 16)
+        ];
+
+        // Classic glyphs of different groups are all different, so this ends at the latest
+        // once every part is back to them
+        loop {
+            let shown: Vec<Vec<String>> = parts.iter()
+                .map(|(style, _, fallbacks, _)| style.glyph.clone().map_or_else(|| fallbacks.clone(), |glyph| vec![glyph]))
+                .collect();
+            let clashing: Vec<usize> = (0..parts.len())
+                .filter(|index| parts[*index].0.glyph != parts[*index].1)
+                .filter(|index| (0..parts.len()).any(|other| parts[other].3 != parts[*index].3
+                    && shown[other].iter().any(|glyph| shown[*index].contains(glyph))))
+                .collect();
+            if clashing.is_empty() {
+                break;
+            }
+
+            for index in clashing {
+                let (style, classic_glyph, _, _) = &mut parts[index];
+                style.glyph = classic_glyph.clone();
+                style.wide = false;
+            }
+        }
+    }
+
+    pub fn get_frame(&self) -> &FrameStyle {
+        &self.frame
+    }
+
+    // Any cell of the field except walls and the player, which depend on their surroundings
+    pub fn get_look(&self, cell: char) -> Look {
+        match cell {
+            '$' => self.look(&self.apple, cell, false),
+            '%' => self.look(&self.golden, cell, false),
+            '!' => self.look(&self.poison, cell, false),
+            '>' => self.look(&self.speed, cell, false),
+            'G' | 'S' | 'M' | 'T' => self.look(&self.power_up, cell, false),
+            '0'..='9' => {
+                let mut look = self.look(&self.portal, cell, false);
+                if look.color.is_none() && !self.portal_colors.is_empty() {
+                    look.color = self.adapt(Some(self.portal_colors[cell as usize % self.portal_colors.len()]));
+                }
+                look
+            }
+            'O' => self.look(&self.opponent_head, cell, false),
+            'o' => self.look(&self.opponent_body, cell, true),
+            '.' => self.look(&self.hint, cell, false),
+            '+' | 'x' => self.look(&self.rising_wall, cell, true),
+            '~' => self.look(&self.ghost, cell, true),
+            _ => self.look(&self.floor, cell, false)
+        }
+    }
+
+    pub fn get_fatal_look(&self) -> Look {
+        self.look(&self.fatal, 'X', false)
+    }
+
+    pub fn get_fog_look(&self) -> Look {
+        self.look(&self.fog, ' ', false)
+    }
+
+    // `neighbors` are the directions in which the wall continues
+    pub fn get_wall_look(&self, neighbors: &[Direction], is_remembered: bool) -> Look {
+        let style = if is_remembered { &self.remembered_wall } else { &self.wall };
+        if !self.connected_walls || (is_remembered && !self.has_colors()) {
+            return self.look(style, '#', true);
+        }
+
+        let has = |direction| neighbors.contains(&direction);
+        let glyph = match (has(Direction::UP), has(Direction::DOWN), has(Direction::LEFT), has(Direction::RIGHT)) {
+            (false, false, false, false) => '■',
+            (_, _, false, false) => '│',
+            (false, false, _, _) => '─',
+            (true, false, true, false) => '┘',
+            (true, false, false, true) => '└',
+            (false, true, true, false) => '┐',
+            (false, true, false, true) => '┌',
+            (true, true, true, false) => '┤',
+            (true, true, false, true) => '├',
+            (true, false, true, true) => '┴',
+            (false, true, true, true) => '┬',
+            (true, true, true, true) => '┼'
+        };
+        let filler = if has(Direction::RIGHT) { "─" } else { " " };
+
+        Look {
+            glyph: glyph.to_string(),
+            filler: filler.to_string(),
+            color: self.adapt(style.color),
+            is_bold: style.bold
+        }
+    }
+
+    // The segment at `index` of the player's snake, counting from the head
+    pub fn get_player_look(&self, index: usize, len: usize) -> Look {
+        if index == 0 {
+            return self.look(&self.head, '@', false);
+        }
+        if index + 1 == len {
+            return self.look(&self.tail, '*', true);
+        }
+
+        let mut look = self.look(&self.body, '*', true);
+        if let (Some(ThemeColor(Color::Rgb { r, g, b })), Some(ThemeColor(Color::Rgb { r: end_r, g: end_g, b: end_b })))
+                = (self.body.color, self.body_gradient) {
+            let progress = index as f64 / len.max(2) as f64;
+            let blend = |from: u8, to: u8| (from as f64 + (to as f64 - from as f64) * progress).round() as u8;
+            look.color = self.adapt(Some(ThemeColor(Color::Rgb { r: blend(r, end_r), g: blend(g, end_g), b: blend(b, end_b) })));
+        }
+        look
+    }
+
+    // Solid parts like bodies fill both characters of a double-width cell, so they stay unbroken
+    fn look(&self, style: &Style, fallback: char, is_solid: bool) -> Look {
+        let glyph = style.glyph.clone().unwrap_or_else(|| fallback.to_string());
+        let filler = if style.wide {
+            String::new()
+        } else if is_solid {
+            glyph.clone()
+        } else {
+            String::from(" ")
+        };
+
+        Look { glyph, filler, color: self.adapt(style.color), is_bold: style.bold }
+    }
+
+    fn adapt(&self, color: Option<ThemeColor>) -> Option<Color> {
+        color.and_then(|ThemeColor(color)| self.color_support.adapt(color))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Every glyph a theme shows, with the part it belongs to; the body and tail, and the floor and fog, count as one
+    fn shown_glyphs(theme: &Theme) -> Vec<(&'static str, String)> {
+        let mut glyphs = vec![
+            ("head", theme.get_player_look(0, 3).glyph),
+            ("body", theme.get_player_look(1, 3).glyph),
+            ("body", theme.get_player_look(2, 3).glyph),
+            ("remembered_wall", theme.get_wall_look(&[], true).glyph),
+            ("fatal", theme.get_fatal_look().glyph),
+            ("floor", theme.get_fog_look().glyph)
+        ];
+        for neighbors in 0..16 {
+            let neighbors: Vec<Direction> = [Direction::UP, Direction::DOWN, Direction::LEFT, Direction::RIGHT]
+                .into_iter()
+                .enumerate()
+                .filter(|(bit, _)| neighbors & (1 << bit) != 0)
+                .map(|(_, direction)| direction)
+                .collect();
+            glyphs.push(("wall", theme.get_wall_look(&neighbors, false).glyph));
+        }
+        let cells = [
+            ("floor", " "), ("apple", "$"), ("golden", "%"), ("poison", "!"), ("speed", ">"),
+            ("power_up", "GSMT"), ("portal", "0123456789"), ("opponent_head", "O"), ("opponent_body", "o"),
+            ("hint", "."), ("rising_wall", "+x"), ("ghost", "~")
+        ];
+        for (part, cells) in cells {
+            glyphs.extend(cells.chars().map(|cell| (part, theme.get_look(cell).glyph)));
+        }
+
+        glyphs
+    }
+
+    #[test]
+    fn parts_keep_distinct_glyphs_without_colors() {
+        for name in ["classic", "box", "emoji", "high_contrast"] {
+            let theme = Theme::load(name).with_palette(Palette::Monochrome);
+            let glyphs = shown_glyphs(&theme);
+
+            for (part, glyph) in &glyphs {
+                for (other_part, other_glyph) in &glyphs {
+                    assert!(part == other_part || glyph != other_glyph,
+                        "{name}: {part} and {other_part} both show {glyph:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rising_walls_blink_without_colors() {
+        let theme = Theme::load("box").with_color_support(ColorSupport::None);
+
+        assert_ne!(theme.get_look('+').glyph, theme.get_look('x').glyph);
+    }
+}
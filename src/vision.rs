@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::Board,
+    point::Point
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct VisionConfig {
+    radius: u32,
+    line_of_sight: bool,
+    compass: bool
+}
+
+impl Default for VisionConfig {
+    fn default() -> VisionConfig {
+        VisionConfig { radius: 0, line_of_sight: true, compass: false }
+    }
+}
+
+impl VisionConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.radius > 0
+    }
+
+    pub fn has_compass(&self) -> bool {
+        self.compass
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Visibility {
+    Visible,
+    // A wall seen earlier, drawn as it was back then
+    Remembered,
+    Hidden
+}
+
+// What the player can see around the head, and what they remember of the rest of the board
+#[derive(Serialize, Deserialize)]
+pub struct Vision {
+    config: VisionConfig,
+    visible: Vec<Vec<bool>>,
+    remembered_walls: Vec<Vec<bool>>
+}
+
+impl Vision {
+    pub fn new(config: &VisionConfig, board: &Board) -> Vision {
+        Vision {
+            config: config.clone(),
+            visible: vec![vec![false; board.get_width()]; board.get_height()],
+            remembered_walls: vec![vec![false; board.get_width()]; board.get_height()]
+        }
+    }
+
+    pub fn get_visibility(&self, point: &Point) -> Visibility {
+        if !self.config.is_enabled() {
+            return Visibility::Visible;
+        }
+
+        let (x, y) = (point.get_x() as usize, point.get_y() as usize);
+        if self.visible[y][x] {
+            Visibility::Visible
+        } else if self.remembered_walls[y][x] {
+            Visibility::Remembered
+        } else {
+            Visibility::Hidden
+        }
+    }
+
+    pub fn update(&mut self, maze: Option<&[Vec<bool>]>, head: &Point) {
+        if !self.config.is_enabled() {
+            return;
+        }
+
+        let (height, width) = (self.visible.len() as i32, self.visible[0].len() as i32);
+        let radius = self.config.radius as i32;
+
+        for row in self.visible.iter_mut() {
+            row.fill(false);
+        }
+
+        // A dead snake's head may be outside the board
+        if head.get_x() < 0 || head.get_y() < 0 || head.get_x() >= width || head.get_y() >= height {
+            return;
+        }
+
+        for y in (head.get_y() - radius).max(0)..(head.get_y() + radius + 1).min(height) {
+            for x in (head.get_x() - radius).max(0)..(head.get_x() + radius + 1).min(width) {
+                let (dx, dy) = (x - head.get_x(), y - head.get_y());
+                if dx * dx + dy * dy > radius * radius {
+                    continue;
+                }
+
+                let cell = Point::new(x, y);
+                if self.config.line_of_sight && let Some(maze) = maze && !is_in_sight(maze, head, &cell) {
+                    continue;
+                }
+
+                let (x, y) = (x as usize, y as usize);
+                self.visible[y][x] = true;
+                self.remembered_walls[y][x] = maze.is_some_and(|maze| maze[y][x]);
+            }
+        }
+    }
+}
+
+// Walks a Bresenham line from `from` to `to`; walls block the view of anything behind them
+fn is_in_sight(maze: &[Vec<bool>], from: &Point, to: &Point) -> bool {
+    let (dx, dy) = ((to.get_x() - from.get_x()).abs(), -(to.get_y() - from.get_y()).abs());
+    let (step_x, step_y) = ((to.get_x() - from.get_x()).signum(), (to.get_y() - from.get_y()).signum());
+    let (mut x, mut y) = (from.get_x(), from.get_y());
+    let mut error = dx + dy;
+
+    while (x, y) != (to.get_x(), to.get_y()) {
+        if (x, y) != (from.get_x(), from.get_y()) && maze[y as usize][x as usize] {
+            return false;
+        }
+
+        let doubled_error = 2 * error;
+        if doubled_error >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if doubled_error <= dx {
+            error += dx;
+            y += step_y;
+        }
+    }
+
+    true
+}
+
+// An arrow pointing from `from` roughly toward `to`
+pub fn get_compass_arrow(from: &Point, to: &Point) -> char {
+    let (dx, dy) = (to.get_x() - from.get_x(), to.get_y() - from.get_y());
+    // A direction only counts if it's at least half as far as the other one
+    let horizontal = if dx.abs() * 2 >= dy.abs() { dx.signum() } else { 0 };
+    let vertical = if dy.abs() * 2 >= dx.abs() { dy.signum() } else { 0 };
+
+    match (horizontal, vertical) {
+        (0, -1) => '↑',
+        (0, 1) => '↓',
+        (-1, 0) => '←',
+        (1, 0) => '→',
+        (-1, -1) => '↖',
+        (1, -1) => '↗',
+        (-1, 1) => '↙',
+        (1, 1) => '↘',
+        _ => '·'
+    }
+}